@@ -0,0 +1,119 @@
+//! Parsing and split benchmarks on synthetic BEAST output. To compare a change
+//! with an earlier revision, save a criterion baseline on a checkout of that
+//! revision and compare against it:
+//!
+//! ```sh
+//! git switch --detach <revision>
+//! cargo bench --bench parse_trees -- --save-baseline before
+//! git switch -
+//! cargo bench --bench parse_trees -- --baseline before
+//! ```
+//!
+//! The parsed trees take tens of megabytes, which glibc returns to the system after
+//! every iteration and which are page faulted in again by the next one. How much
+//! of that shows up depends on earlier allocations (revisions which lexed the whole
+//! input up front kept the heap), so set `MALLOC_TRIM_THRESHOLD_=4000000000` and
+//! `MALLOC_MMAP_THRESHOLD_=1000000000` for both runs to compare only the parsing.
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use nexus::{Lexer, NexusBlock, Parser, Tokens};
+
+/// Generates a NEXUS file resembling a BEAST posterior sample: a TAXA block, a
+/// TREES block with a numeric translate table and `n_trees` random binary trees
+/// with branch lengths on `n_taxa` leaves.
+fn beast_output(n_taxa: usize, n_trees: usize) -> String {
+    let mut seed: u64 = 42;
+    let mut random = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    let mut content = String::from("#NEXUS\n\nBegin taxa;\n\tDimensions ntax=");
+    content.push_str(&format!("{};\n\t\tTaxlabels\n", n_taxa));
+    for taxon in 0..n_taxa {
+        content.push_str(&format!("\t\t\ttaxon_{}\n", taxon));
+    }
+    content.push_str("\t\t\t;\nEnd;\n\nBegin trees;\n\tTranslate\n");
+    for taxon in 0..n_taxa {
+        let separator = if taxon + 1 == n_taxa { "" } else { "," };
+        content.push_str(&format!("\t\t{} taxon_{}{}\n", taxon + 1, taxon, separator));
+    }
+    content.push_str(";\n");
+
+    for state in 0..n_trees {
+        let mut subtrees: Vec<String> = (1..=n_taxa)
+            .map(|taxon| format!("{}:{}", taxon, (random() % 1000) as f64 / 100.0))
+            .collect();
+        while subtrees.len() > 1 {
+            let first = subtrees.swap_remove(random() as usize % subtrees.len());
+            let second = subtrees.swap_remove(random() as usize % subtrees.len());
+            subtrees.push(format!(
+                "({},{}):{}",
+                first,
+                second,
+                (random() % 1000) as f64 / 100.0
+            ));
+        }
+        let root = subtrees.pop().unwrap();
+        let root = &root[..root.rfind(':').unwrap()];
+        content.push_str(&format!("tree STATE_{} = {};\n", state * 1000, root));
+    }
+
+    content.push_str("End;\n");
+    content
+}
+
+fn parse(content: &str, parallel_trees: bool) {
+    let lexer = Lexer::new(content);
+    let tokens = Tokens::new(&lexer);
+    let mut parser = Parser::new(tokens).with_parallel_trees(parallel_trees);
+    parser.parse().unwrap();
+}
+
+fn bench_parse_trees(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_trees");
+    group.sample_size(10);
+
+    for (n_taxa, n_trees) in [(50, 1_000), (500, 200)] {
+        let content = beast_output(n_taxa, n_trees);
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_function(format!("{}_taxa_{}_trees", n_taxa, n_trees), |b| {
+            b.iter(|| parse(&content, false))
+        });
+        group.bench_function(format!("{}_taxa_{}_trees_parallel", n_taxa, n_trees), |b| {
+            b.iter(|| parse(&content, true))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_splits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("splits");
+    group.sample_size(10);
+
+    for (n_taxa, n_trees) in [(50, 1_000), (500, 200)] {
+        let content = beast_output(n_taxa, n_trees);
+        let lexer = Lexer::new(&content);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse().unwrap();
+        let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.get(1) else {
+            panic!("Expected a trees block.");
+        };
+
+        group.throughput(Throughput::Elements(n_trees as u64));
+        group.bench_function(format!("{}_taxa_{}_trees", n_taxa, n_trees), |b| {
+            b.iter(|| {
+                for tree in trees {
+                    tree.splits(nexus.taxa.len());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_trees, bench_splits);
+criterion_main!(benches);
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use crate::types::{clades::Clade, tree::Tree};
+
+/// How often a clade was seen and the sum of its branch lengths.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct CladeStats {
+    pub count: usize,
+    pub length_sum: f64,
+    /// The number of occurrences with a branch length.
+    pub length_count: usize,
+}
+
+impl CladeStats {
+    pub fn mean_length(&self) -> Option<f64> {
+        (self.length_count > 0).then(|| self.length_sum / self.length_count as f64)
+    }
+}
+
+/// Counts the clades of a sample of trees, e.g. of a posterior read one tree at a
+/// time with a [`TreeReader`](crate::TreeReader). Leaves are counted as clades too,
+/// to average their branch lengths.
+///
+/// By default trees are taken as rooted. Unrooted trees are compared by their
+/// normalized splits (see [`Tree::split_lengths`]) instead.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CladeCounts {
+    taxa_count: usize,
+    unrooted: bool,
+    tree_count: usize,
+    taxa: Clade,
+    clades: HashMap<Clade, CladeStats>,
+}
+
+impl CladeCounts {
+    /// `taxa_count` is the number of taxa of the file the trees belong to.
+    pub fn new(taxa_count: usize) -> Self {
+        Self {
+            taxa_count,
+            unrooted: false,
+            tree_count: 0,
+            taxa: Clade::with_capacity(taxa_count),
+            clades: HashMap::new(),
+        }
+    }
+
+    pub fn with_unrooted(mut self, unrooted: bool) -> Self {
+        self.unrooted = unrooted;
+        self
+    }
+
+    pub fn add(&mut self, tree: &Tree) {
+        self.tree_count += 1;
+
+        let mut add_clade = |clade: Clade, length: Option<f64>| {
+            let stats = self.clades.entry(clade).or_default();
+            stats.count += 1;
+            if let Some(length) = length {
+                stats.length_sum += length;
+                stats.length_count += 1;
+            }
+        };
+
+        if self.unrooted {
+            // split lengths default to zero, which must not be averaged
+            let has_lengths = tree.leaves().any(|leaf| tree.node(leaf).length().is_some());
+            for (split, length) in tree.split_lengths(self.taxa_count) {
+                add_clade(split, has_lengths.then_some(length));
+            }
+            for leaf in tree.leaves() {
+                self.taxa.extend(tree.node(leaf).taxon());
+            }
+        } else {
+            let mut node_clades = tree.node_clades(self.taxa_count);
+            if let Some((_, taxa)) = node_clades.pop() {
+                self.taxa.union_with(&taxa);
+            }
+            for (node_id, clade) in node_clades {
+                add_clade(clade, tree.node(node_id).length());
+            }
+        }
+    }
+
+    pub fn taxa_count(&self) -> usize {
+        self.taxa_count
+    }
+
+    pub fn is_unrooted(&self) -> bool {
+        self.unrooted
+    }
+
+    pub fn tree_count(&self) -> usize {
+        self.tree_count
+    }
+
+    /// All taxa seen in the trees.
+    pub fn taxa(&self) -> &Clade {
+        &self.taxa
+    }
+
+    pub fn get(&self, clade: &Clade) -> Option<&CladeStats> {
+        self.clades.get(clade)
+    }
+
+    /// The fraction of trees containing the clade. Clades of unrooted trees have
+    /// to be normalized splits.
+    pub fn frequency(&self, clade: &Clade) -> f64 {
+        match (self.clades.get(clade), self.tree_count) {
+            (Some(stats), tree_count) if tree_count > 0 => stats.count as f64 / tree_count as f64,
+            _ => 0.0,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Clade, &CladeStats)> {
+        self.clades.iter()
+    }
+}
+
+impl<'t> Extend<&'t Tree> for CladeCounts {
+    fn extend<I: IntoIterator<Item = &'t Tree>>(&mut self, trees: I) {
+        for tree in trees {
+            self.add(tree);
+        }
+    }
+}
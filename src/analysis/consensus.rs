@@ -0,0 +1,143 @@
+use crate::{
+    analysis::clade_counts::CladeCounts,
+    types::{
+        clades::Clade,
+        tree::{AttributeValue, Tree, TreeNode},
+    },
+};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ConsensusMethod {
+    /// The clades found in all trees.
+    Strict,
+    /// The clades found in more than half of the trees.
+    MajorityRule,
+    /// The majority-rule clades, extended greedily by the most frequent clades that
+    /// are compatible with the ones chosen so far.
+    ExtendedMajorityRule,
+}
+
+/// The consensus of a sample of trees on the same taxa. See [`CladeCounts::consensus`].
+pub fn consensus<'t>(
+    trees: impl IntoIterator<Item = &'t Tree>,
+    taxa_count: usize,
+    method: ConsensusMethod,
+) -> Tree {
+    let mut clade_counts = CladeCounts::new(taxa_count);
+    clade_counts.extend(trees);
+    clade_counts.consensus(method)
+}
+
+impl CladeCounts {
+    /// Builds the consensus tree of the counted trees. Every internal node has the
+    /// frequency of its clade as `frequency` attribute, and every node the mean
+    /// length of its branch in the trees containing its clade.
+    ///
+    /// Consensus trees of unrooted trees are unrooted, with the first taxon below
+    /// their root.
+    pub fn consensus(&self, method: ConsensusMethod) -> Tree {
+        let tree_count = self.tree_count();
+        let mut candidates: Vec<(&Clade, usize)> = self
+            .iter()
+            .filter(|(clade, _)| {
+                // unrooted splits need two taxa on their other side as well
+                let size = clade.count_ones(..);
+                let min_other_size = if self.is_unrooted() { 2 } else { 1 };
+                size >= 2 && self.taxa().count_ones(..) - size >= min_other_size
+            })
+            .map(|(clade, stats)| (clade, stats.count))
+            .filter(|(_, count)| match method {
+                ConsensusMethod::Strict => *count == tree_count,
+                ConsensusMethod::MajorityRule => 2 * count > tree_count,
+                ConsensusMethod::ExtendedMajorityRule => true,
+            })
+            .collect();
+        // the most frequent clades first, in a deterministic order
+        candidates.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+        let mut chosen: Vec<&Clade> = vec![];
+        for (clade, _) in candidates {
+            // majority-rule clades are always compatible with each other
+            if chosen.iter().all(|other| compatible(clade, other)) {
+                chosen.push(clade);
+            }
+        }
+
+        self.build_tree(chosen, method)
+    }
+
+    fn build_tree(&self, mut clades: Vec<&Clade>, method: ConsensusMethod) -> Tree {
+        let mut tree = Tree {
+            tree: Default::default(),
+            name: match method {
+                ConsensusMethod::Strict => "strict_consensus",
+                ConsensusMethod::MajorityRule => "majority_rule_consensus",
+                ConsensusMethod::ExtendedMajorityRule => "extended_majority_rule_consensus",
+            }
+            .to_string(),
+            rooted: !self.is_unrooted(),
+        };
+        let root = tree.tree.new_node(TreeNode::new_root());
+
+        // parents are placed before their children, so that the parent of a clade
+        // is the smallest placed clade containing it
+        clades.sort_by_key(|clade| std::cmp::Reverse(clade.count_ones(..)));
+        let mut placed: Vec<(&Clade, _)> = vec![];
+        for clade in clades {
+            let parent = placed
+                .iter()
+                .rev()
+                .find(|(other, _)| clade.is_subset(other))
+                .map_or(root, |(_, node_id)| *node_id);
+
+            let mut node = TreeNode::new_internal()
+                .with_attribute("frequency", AttributeValue::Number(self.frequency(clade)));
+            if let Some(length) = self.get(clade).and_then(|stats| stats.mean_length()) {
+                node = node.with_length(length);
+            }
+
+            let node_id = tree.tree.new_node(node);
+            parent.append(node_id, &mut tree.tree);
+            placed.push((clade, node_id));
+        }
+
+        for taxon in self.taxa().ones() {
+            let mut leaf_clade = Clade::with_capacity(self.taxa_count());
+            leaf_clade.insert(taxon);
+
+            let parent = placed
+                .iter()
+                .rev()
+                .find(|(other, _)| other.contains(taxon))
+                .map_or(root, |(_, node_id)| *node_id);
+
+            let mut leaf = TreeNode::new_leaf(taxon);
+            if let Some(length) = self.leaf_length(taxon, leaf_clade) {
+                leaf = leaf.with_length(length);
+            }
+            let leaf_id = tree.tree.new_node(leaf);
+            parent.append(leaf_id, &mut tree.tree);
+        }
+
+        tree
+    }
+
+    /// The mean length of the branch of a leaf. In unrooted trees, the split of the
+    /// first taxon is normalized to all other taxa.
+    fn leaf_length(&self, taxon: usize, leaf_clade: Clade) -> Option<f64> {
+        let split = match self.is_unrooted() && self.taxa().minimum() == Some(taxon) {
+            true => {
+                let mut split = self.taxa().clone();
+                split.set(taxon, false);
+                split
+            }
+            false => leaf_clade,
+        };
+        self.get(&split).and_then(|stats| stats.mean_length())
+    }
+}
+
+/// Whether two clades can be in the same tree, i.e. are nested or disjoint.
+pub(crate) fn compatible(a: &Clade, b: &Clade) -> bool {
+    a.is_disjoint(b) || a.is_subset(b) || b.is_subset(a)
+}
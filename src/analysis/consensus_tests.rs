@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        analysis::{
+            clade_counts::CladeCounts,
+            consensus::{ConsensusMethod, consensus},
+        },
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::{parser::Parser, tree_reader::TreeReader},
+        types::{
+            clades::Clade,
+            nexus::{Nexus, NexusBlock},
+            tree::{AttributeValue, Tree, TreeNode},
+        },
+        writer::newick::write_newick,
+    };
+
+    const POSTERIOR: &str = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=5;
+            TAXLABELS A B C D E;
+        END;
+        BEGIN TREES;
+            TREE t1 = ((((A:1,B:1):1,C:2):1,D:3):1,E:4);
+            TREE t2 = ((((A:1,B:1):3,D:4):1,C:5):1,E:6);
+            TREE t3 = ((((A:1,C:1):1,B:2):1,D:3):1,E:4);
+            TREE t4 = ((((A:1,B:1):1,C:2):1,D:3):1,E:4);
+        END;";
+
+    fn parse(text: &str) -> Nexus {
+        let lexer = Lexer::new(text);
+        Parser::new(Tokens::new(&lexer)).parse().unwrap()
+    }
+
+    fn trees(nexus: &Nexus) -> &[Tree] {
+        match nexus.blocks.get(1) {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    fn clade(taxa: &[usize]) -> Clade {
+        let mut clade = Clade::with_capacity(5);
+        clade.extend(taxa.iter().copied());
+        clade
+    }
+
+    fn sorted_clades(tree: &Tree) -> Vec<Clade> {
+        let mut clades = tree.clades(5);
+        clades.sort();
+        clades
+    }
+
+    fn node_of<'t>(tree: &'t Tree, taxa: &[usize]) -> &'t TreeNode {
+        let (node_id, _) = tree
+            .node_clades(5)
+            .into_iter()
+            .find(|(_, other)| *other == clade(taxa))
+            .unwrap();
+        tree.node(node_id)
+    }
+
+    #[test]
+    fn test_strict_and_majority_rule_consensus() {
+        let nexus = parse(POSTERIOR);
+        let trees = trees(&nexus);
+
+        let strict = consensus(trees, 5, ConsensusMethod::Strict);
+        assert!(strict.rooted);
+        assert_eq!(
+            sorted_clades(&strict),
+            vec![clade(&[0, 1, 2, 3]), clade(&[0, 1, 2, 3, 4])]
+        );
+
+        let majority = consensus(trees, 5, ConsensusMethod::MajorityRule);
+        let mut expected = vec![
+            clade(&[0, 1]),
+            clade(&[0, 1, 2]),
+            clade(&[0, 1, 2, 3]),
+            clade(&[0, 1, 2, 3, 4]),
+        ];
+        expected.sort();
+        assert_eq!(sorted_clades(&majority), expected);
+
+        let a_b = node_of(&majority, &[0, 1]);
+        assert_eq!(
+            a_b.attributes().get("frequency"),
+            Some(&AttributeValue::Number(0.75))
+        );
+        assert_eq!(a_b.length(), Some(5.0 / 3.0));
+        assert_eq!(
+            node_of(&majority, &[0, 1, 2, 3])
+                .attributes()
+                .get("frequency"),
+            Some(&AttributeValue::Number(1.0))
+        );
+        assert_eq!(node_of(&majority, &[4]).length(), Some(4.5));
+    }
+
+    #[test]
+    fn test_extended_majority_rule_consensus() {
+        let nexus = parse(
+            "#NEXUS
+            BEGIN TREES;
+                TREE t1 = ((A,B),(C,D));
+                TREE t2 = ((A,B),(C,D));
+                TREE t3 = ((A,C),(B,D));
+                TREE t4 = ((A,D),(B,C));
+            END;",
+        );
+        let trees = match nexus.blocks.first() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        };
+
+        let majority = consensus(trees, 4, ConsensusMethod::MajorityRule);
+        assert_eq!(majority.clades(4).len(), 1);
+
+        let extended = consensus(trees, 4, ConsensusMethod::ExtendedMajorityRule);
+        let mut clades = extended.clades(4);
+        clades.sort();
+        let mut expected: Vec<Clade> = [vec![0, 1], vec![2, 3], vec![0, 1, 2, 3]]
+            .iter()
+            .map(|taxa| {
+                let mut clade = Clade::with_capacity(4);
+                clade.extend(taxa.iter().copied());
+                clade
+            })
+            .collect();
+        expected.sort();
+        assert_eq!(clades, expected);
+    }
+
+    #[test]
+    fn test_consensus_of_streamed_trees() {
+        let nexus = parse(POSTERIOR);
+
+        let mut reader = TreeReader::new(Cursor::new(POSTERIOR)).unwrap();
+        let mut clade_counts = CladeCounts::new(5);
+        for tree in &mut reader {
+            clade_counts.add(&tree.unwrap());
+        }
+
+        assert_eq!(clade_counts.tree_count(), 4);
+        assert_eq!(
+            clade_counts.consensus(ConsensusMethod::MajorityRule),
+            consensus(trees(&nexus), 5, ConsensusMethod::MajorityRule)
+        );
+    }
+
+    #[test]
+    fn test_unrooted_consensus() {
+        let nexus = parse(
+            "#NEXUS
+            BEGIN TAXA;
+                DIMENSIONS NTAX=5;
+                TAXLABELS A B C D E;
+            END;
+            BEGIN TREES;
+                TREE t1 = ((A,B),(C,(D,E)));
+                TREE t2 = (A,(B,(C,(D,E))));
+                TREE t3 = ((D,E),(C,(A,B)));
+            END;",
+        );
+        let trees = trees(&nexus);
+
+        let mut clade_counts = CladeCounts::new(5).with_unrooted(true);
+        clade_counts.extend(trees);
+        let consensus = clade_counts.consensus(ConsensusMethod::Strict);
+
+        assert!(!consensus.rooted);
+        assert_eq!(consensus.splits(5), trees[0].splits(5));
+        assert_eq!(
+            write_newick(&consensus, &nexus.taxa),
+            "(((D,E)[&frequency=1],C)[&frequency=1],A,B);"
+        );
+    }
+}
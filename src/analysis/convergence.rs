@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use crate::types::{clades::Clade, taxa::Taxa, tree::Tree};
+
+/// The frequencies of a split in every run and their standard deviation.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SplitFrequency {
+    /// The split, normalized as in [`Tree::split_lengths`].
+    pub split: Clade,
+    pub frequencies: Vec<f64>,
+    /// The sample standard deviation of the frequencies.
+    pub standard_deviation: f64,
+}
+
+impl SplitFrequency {
+    pub fn mean_frequency(&self) -> f64 {
+        self.frequencies.iter().sum::<f64>() / self.frequencies.len() as f64
+    }
+}
+
+/// Compares the split frequencies of several independent runs, e.g. posteriors of
+/// BEAST or MrBayes read one tree at a time with [`TreeReader`](crate::TreeReader)s,
+/// like MrBayes' average standard deviation of split frequencies (ASDSF).
+///
+/// Trees are taken as unrooted and only their non-trivial splits are counted. The
+/// first `burnin` trees of every run are discarded, and splits whose frequency is
+/// below the minimum frequency in all runs are left out of the diagnostics, as
+/// with MrBayes' `minpartfreq`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SplitFrequencies {
+    taxa_count: usize,
+    burnin: usize,
+    min_frequency: f64,
+    /// The number of trees of every run, including the burn-in.
+    trees_seen: Vec<usize>,
+    /// The number of trees of every run after the burn-in.
+    tree_counts: Vec<usize>,
+    /// The number of trees with every split in every run.
+    splits: HashMap<Clade, Vec<usize>>,
+}
+
+/// The split frequencies of several runs, with the given burn-in and minimum
+/// frequency (see [`SplitFrequencies`]).
+pub fn split_frequencies<'t, R: IntoIterator<Item = &'t Tree>>(
+    runs: impl IntoIterator<Item = R>,
+    taxa_count: usize,
+    burnin: usize,
+    min_frequency: f64,
+) -> SplitFrequencies {
+    let mut frequencies = SplitFrequencies::new(taxa_count)
+        .with_burnin(burnin)
+        .with_min_frequency(min_frequency);
+    for run in runs {
+        frequencies.add_run(run);
+    }
+    frequencies
+}
+
+impl SplitFrequencies {
+    /// `taxa_count` is the number of taxa of the files the trees belong to. The
+    /// burn-in defaults to no trees and the minimum frequency to 0.1.
+    pub fn new(taxa_count: usize) -> Self {
+        Self {
+            taxa_count,
+            burnin: 0,
+            min_frequency: 0.1,
+            trees_seen: vec![],
+            tree_counts: vec![],
+            splits: HashMap::new(),
+        }
+    }
+
+    /// The number of trees discarded at the start of every run.
+    pub fn with_burnin(mut self, burnin: usize) -> Self {
+        self.burnin = burnin;
+        self
+    }
+
+    pub fn with_min_frequency(mut self, min_frequency: f64) -> Self {
+        self.min_frequency = min_frequency;
+        self
+    }
+
+    /// Adds a tree of the run with the given index, starting at 0. Runs are
+    /// created as needed, so trees of different runs can be added in any order.
+    pub fn add(&mut self, run: usize, tree: &Tree) {
+        if run >= self.trees_seen.len() {
+            self.trees_seen.resize(run + 1, 0);
+            self.tree_counts.resize(run + 1, 0);
+        }
+
+        self.trees_seen[run] += 1;
+        if self.trees_seen[run] <= self.burnin {
+            return;
+        }
+        self.tree_counts[run] += 1;
+
+        for split in tree.splits(self.taxa_count) {
+            let counts = self.splits.entry(split).or_default();
+            if run >= counts.len() {
+                counts.resize(run + 1, 0);
+            }
+            counts[run] += 1;
+        }
+    }
+
+    /// Adds all trees of a new run.
+    pub fn add_run<'t>(&mut self, trees: impl IntoIterator<Item = &'t Tree>) {
+        let run = self.run_count();
+        self.trees_seen.push(0);
+        self.tree_counts.push(0);
+        for tree in trees {
+            self.add(run, tree);
+        }
+    }
+
+    pub fn run_count(&self) -> usize {
+        self.trees_seen.len()
+    }
+
+    /// The number of trees of every run after the burn-in.
+    pub fn tree_counts(&self) -> &[usize] {
+        &self.tree_counts
+    }
+
+    /// The splits with at least the minimum frequency in one of the runs, from the
+    /// most to the least frequent on average.
+    pub fn table(&self) -> Vec<SplitFrequency> {
+        let mut table: Vec<SplitFrequency> = self
+            .splits
+            .iter()
+            .filter_map(|(split, counts)| {
+                let frequencies: Vec<f64> = self
+                    .tree_counts
+                    .iter()
+                    .enumerate()
+                    .map(|(run, tree_count)| match tree_count {
+                        0 => 0.0,
+                        _ => *counts.get(run).unwrap_or(&0) as f64 / *tree_count as f64,
+                    })
+                    .collect();
+                if frequencies
+                    .iter()
+                    .all(|frequency| *frequency < self.min_frequency)
+                {
+                    return None;
+                }
+
+                Some(SplitFrequency {
+                    split: split.clone(),
+                    standard_deviation: standard_deviation(&frequencies),
+                    frequencies,
+                })
+            })
+            .collect();
+
+        table.sort_by(|a, b| {
+            b.mean_frequency()
+                .total_cmp(&a.mean_frequency())
+                .then_with(|| a.split.cmp(&b.split))
+        });
+        table
+    }
+
+    /// The average standard deviation of split frequencies, or `None` with fewer
+    /// than two runs or no split above the minimum frequency.
+    pub fn asdsf(&self) -> Option<f64> {
+        let table = self.table();
+        (self.run_count() >= 2 && !table.is_empty()).then(|| {
+            table
+                .iter()
+                .map(|split| split.standard_deviation)
+                .sum::<f64>()
+                / table.len() as f64
+        })
+    }
+
+    /// The maximum standard deviation of split frequencies, or `None` with fewer
+    /// than two runs or no split above the minimum frequency.
+    pub fn max_sdsf(&self) -> Option<f64> {
+        if self.run_count() < 2 {
+            return None;
+        }
+        self.table()
+            .iter()
+            .map(|split| split.standard_deviation)
+            .max_by(f64::total_cmp)
+    }
+
+    /// Writes the table as tab-separated values with a header, one column per run
+    /// and one row per split, for plotting the frequencies of the runs against
+    /// each other. Splits are written as the labels of their taxa, e.g. `{C,D}`.
+    pub fn to_tsv(&self, taxa: &Taxa) -> String {
+        let mut tsv = String::from("split");
+        for run in 1..=self.run_count() {
+            tsv.push_str(&format!("\trun_{}", run));
+        }
+        tsv.push_str("\tsd\n");
+
+        for split in self.table() {
+            let labels: Vec<&str> = split
+                .split
+                .ones()
+                .map(|taxon| taxa.label(taxon).unwrap_or_default())
+                .collect();
+            tsv.push_str(&format!("{{{}}}", labels.join(",")));
+            for frequency in &split.frequencies {
+                tsv.push_str(&format!("\t{}", frequency));
+            }
+            tsv.push_str(&format!("\t{}\n", split.standard_deviation));
+        }
+
+        tsv
+    }
+}
+
+/// The sample standard deviation, 0 for fewer than two values.
+fn standard_deviation(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    (squares / (values.len() - 1) as f64).sqrt()
+}
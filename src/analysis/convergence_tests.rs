@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::convergence::{SplitFrequencies, split_frequencies},
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{clades::Clade, nexus::Nexus, nexus::NexusBlock, tree::Tree},
+    };
+
+    fn parse(newick: &str) -> Nexus {
+        let lexer = Lexer::new(newick);
+        Parser::new(Tokens::new(&lexer)).parse_newick().unwrap()
+    }
+
+    fn trees(nexus: &Nexus) -> &[Tree] {
+        match nexus.blocks.first() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    fn clade(taxa_count: usize, taxa: &[usize]) -> Clade {
+        let mut clade = Clade::with_capacity(taxa_count);
+        clade.extend(taxa.iter().copied());
+        clade
+    }
+
+    // both runs start with a burn-in tree; the first run then has AB|CD twice, the
+    // second one AB|CD and AC|BD once
+    const RUNS: &str = "(A,(B,C),D);((A,B),(C,D));((B,A),(D,C));
+        (A,(B,C),D);((A,B),(C,D));((A,C),(B,D));";
+
+    #[test]
+    fn test_split_frequencies() {
+        let nexus = parse(RUNS);
+        let trees = trees(&nexus);
+        let frequencies = split_frequencies([&trees[..3], &trees[3..]], 4, 1, 0.1);
+
+        assert_eq!(frequencies.run_count(), 2);
+        assert_eq!(frequencies.tree_counts(), &[2, 2]);
+
+        let table = frequencies.table();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[0].split, clade(4, &[2, 3]));
+        assert_eq!(table[0].frequencies, vec![1.0, 0.5]);
+        assert_eq!(table[1].split, clade(4, &[1, 3]));
+        assert_eq!(table[1].frequencies, vec![0.0, 0.5]);
+
+        let sd = 0.125f64.sqrt();
+        assert!((table[0].standard_deviation - sd).abs() < 1e-12);
+        assert!((frequencies.asdsf().unwrap() - sd).abs() < 1e-12);
+        assert!((frequencies.max_sdsf().unwrap() - sd).abs() < 1e-12);
+
+        assert_eq!(
+            frequencies.to_tsv(&nexus.taxa).lines().collect::<Vec<_>>(),
+            vec![
+                "split\trun_1\trun_2\tsd",
+                &format!("{{C,D}}\t1\t0.5\t{}", sd),
+                &format!("{{B,D}}\t0\t0.5\t{}", sd),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_frequency() {
+        let nexus = parse(RUNS);
+        let trees = trees(&nexus);
+        let mut frequencies = SplitFrequencies::new(4)
+            .with_burnin(1)
+            .with_min_frequency(0.6);
+        // runs can be filled one tree at a time, e.g. from readers in lockstep
+        for index in 0..3 {
+            frequencies.add(0, &trees[index]);
+            frequencies.add(1, &trees[index + 3]);
+        }
+
+        let table = frequencies.table();
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[0].frequencies, vec![1.0, 0.5]);
+        assert_eq!(frequencies.asdsf(), frequencies.max_sdsf());
+    }
+
+    #[test]
+    fn test_single_run() {
+        let nexus = parse(RUNS);
+        let mut frequencies = SplitFrequencies::new(4);
+        frequencies.add_run(trees(&nexus));
+
+        assert_eq!(frequencies.table().len(), 3);
+        assert_eq!(frequencies.asdsf(), None);
+        assert_eq!(frequencies.max_sdsf(), None);
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    analysis::{
+        quartets::{TreeNodes, different_quartets},
+        triplets::different_triplets,
+    },
+    types::{clades::Clade, tree::Tree},
+};
+
+/// Distances between trees on the same taxa. Trees are compared as unrooted, by
+/// their normalized splits, except for the triplet distance.
+///
+/// The quartet and triplet distances only count the taxa that are in both trees.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum TreeDistance {
+    /// The number of non-trivial splits found in only one of the trees.
+    RobinsonFoulds,
+    /// The Robinson–Foulds distance divided by its maximum for binary trees,
+    /// 2(n - 3) for n taxa.
+    NormalizedRobinsonFoulds,
+    /// The sum of the absolute differences of the branch lengths of all splits,
+    /// where a split missing from a tree has length 0.
+    WeightedRobinsonFoulds,
+    /// The branch score of Kuhner and Felsenstein, i.e. the square root of the sum
+    /// of the squared differences of the branch lengths of all splits.
+    BranchScore,
+    /// The number of sets of four taxa whose induced topologies differ, i.e. that
+    /// are resolved differently or resolved in only one of the trees. It takes
+    /// O(d·n²) time and O(d·n) memory for n taxa and nodes of degree up to d: the
+    /// pairs of nodes of both trees are compared, which is slower than the
+    /// O(d·n log n) algorithm of Brodal et al. (2013) but simpler.
+    Quartet,
+    /// The number of sets of three taxa whose induced topologies differ in the
+    /// trees seen as rooted. It takes O(n log³ n) time and O(n) memory for n taxa,
+    /// counting the triplets of every node of the first tree as in Brodal et al.
+    /// (2013) but with a heavy path decomposition of the second tree.
+    Triplet,
+}
+
+/// The splits of a tree, computed once per tree for distance matrices.
+struct TreeSplits {
+    splits: Vec<Clade>,
+    lengths: HashMap<Clade, f64>,
+    taxa_in_tree: usize,
+    nodes: Option<TreeNodes>,
+}
+
+impl TreeSplits {
+    fn new(tree: &Tree, taxa_count: usize, distance: TreeDistance) -> Self {
+        let weighted = matches!(
+            distance,
+            TreeDistance::WeightedRobinsonFoulds | TreeDistance::BranchScore
+        );
+
+        Self {
+            splits: match weighted {
+                true => vec![],
+                false => tree.splits(taxa_count),
+            },
+            lengths: match weighted {
+                true => tree.split_lengths(taxa_count),
+                false => HashMap::new(),
+            },
+            taxa_in_tree: tree.leaves().count(),
+            nodes: matches!(distance, TreeDistance::Quartet | TreeDistance::Triplet)
+                .then(|| TreeNodes::new(tree)),
+        }
+    }
+}
+
+impl TreeDistance {
+    /// The distance between two trees. `taxa_count` is the number of taxa of the
+    /// file the trees belong to.
+    pub fn between(self, a: &Tree, b: &Tree, taxa_count: usize) -> f64 {
+        self.between_splits(
+            &TreeSplits::new(a, taxa_count, self),
+            &TreeSplits::new(b, taxa_count, self),
+        )
+    }
+
+    fn between_splits(self, a: &TreeSplits, b: &TreeSplits) -> f64 {
+        match self {
+            TreeDistance::RobinsonFoulds => unshared_splits(&a.splits, &b.splits) as f64,
+            TreeDistance::NormalizedRobinsonFoulds => {
+                let taxa_in_trees = a.taxa_in_tree.max(b.taxa_in_tree);
+                match taxa_in_trees > 3 {
+                    true => {
+                        unshared_splits(&a.splits, &b.splits) as f64
+                            / (2 * (taxa_in_trees - 3)) as f64
+                    }
+                    false => 0.0,
+                }
+            }
+            TreeDistance::WeightedRobinsonFoulds => length_differences(a, b)
+                .map(|difference| difference.abs())
+                .sum(),
+            TreeDistance::BranchScore => length_differences(a, b)
+                .map(|difference| difference * difference)
+                .sum::<f64>()
+                .sqrt(),
+            TreeDistance::Quartet | TreeDistance::Triplet => {
+                let (Some(a_nodes), Some(b_nodes)) = (&a.nodes, &b.nodes) else {
+                    return 0.0;
+                };
+                match self {
+                    TreeDistance::Quartet => different_quartets(a_nodes, b_nodes) as f64,
+                    _ => different_triplets(a_nodes, b_nodes) as f64,
+                }
+            }
+        }
+    }
+}
+
+/// Counts the splits which are in only one of two sorted lists.
+fn unshared_splits(a: &[Clade], b: &[Clade]) -> usize {
+    let (mut i, mut j, mut unshared) = (0, 0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => {
+                unshared += 1;
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                unshared += 1;
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    unshared + (a.len() - i) + (b.len() - j)
+}
+
+fn length_differences<'s>(a: &'s TreeSplits, b: &'s TreeSplits) -> impl Iterator<Item = f64> + 's {
+    let in_a = a
+        .lengths
+        .iter()
+        .map(|(split, length)| length - b.lengths.get(split).copied().unwrap_or_default());
+    let only_in_b = b
+        .lengths
+        .iter()
+        .filter(|(split, _)| !a.lengths.contains_key(*split))
+        .map(|(_, length)| *length);
+
+    in_a.chain(only_in_b)
+}
+
+pub fn robinson_foulds(a: &Tree, b: &Tree, taxa_count: usize) -> usize {
+    TreeDistance::RobinsonFoulds.between(a, b, taxa_count) as usize
+}
+
+pub fn normalized_robinson_foulds(a: &Tree, b: &Tree, taxa_count: usize) -> f64 {
+    TreeDistance::NormalizedRobinsonFoulds.between(a, b, taxa_count)
+}
+
+pub fn weighted_robinson_foulds(a: &Tree, b: &Tree, taxa_count: usize) -> f64 {
+    TreeDistance::WeightedRobinsonFoulds.between(a, b, taxa_count)
+}
+
+pub fn branch_score(a: &Tree, b: &Tree, taxa_count: usize) -> f64 {
+    TreeDistance::BranchScore.between(a, b, taxa_count)
+}
+
+pub fn quartet_distance(a: &Tree, b: &Tree, taxa_count: usize) -> usize {
+    TreeDistance::Quartet.between(a, b, taxa_count) as usize
+}
+
+pub fn triplet_distance(a: &Tree, b: &Tree, taxa_count: usize) -> usize {
+    TreeDistance::Triplet.between(a, b, taxa_count) as usize
+}
+
+/// The symmetric matrix of the distances between all pairs of trees, e.g. of the
+/// trees of a trees block. The splits of every tree are computed once, and with
+/// the `parallel` feature both they and the rows of the matrix are computed in
+/// parallel.
+pub fn distance_matrix(trees: &[Tree], taxa_count: usize, distance: TreeDistance) -> Vec<Vec<f64>> {
+    let splits = |tree: &Tree| TreeSplits::new(tree, taxa_count, distance);
+    #[cfg(feature = "parallel")]
+    let splits: Vec<TreeSplits> = trees.par_iter().map(splits).collect();
+    #[cfg(not(feature = "parallel"))]
+    let splits: Vec<TreeSplits> = trees.iter().map(splits).collect();
+
+    // the upper triangle, row by row
+    let row = |i: usize| -> Vec<f64> {
+        splits[i + 1..]
+            .iter()
+            .map(|other| distance.between_splits(&splits[i], other))
+            .collect()
+    };
+    #[cfg(feature = "parallel")]
+    let rows: Vec<Vec<f64>> = (0..trees.len()).into_par_iter().map(row).collect();
+    #[cfg(not(feature = "parallel"))]
+    let rows: Vec<Vec<f64>> = (0..trees.len()).map(row).collect();
+
+    let mut matrix = vec![vec![0.0; trees.len()]; trees.len()];
+    for (i, row) in rows.into_iter().enumerate() {
+        for (offset, value) in row.into_iter().enumerate() {
+            matrix[i][i + 1 + offset] = value;
+            matrix[i + 1 + offset][i] = value;
+        }
+    }
+
+    matrix
+}
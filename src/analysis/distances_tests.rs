@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::distances::{
+            TreeDistance, branch_score, distance_matrix, normalized_robinson_foulds,
+            robinson_foulds, weighted_robinson_foulds,
+        },
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{nexus::NexusBlock, tree::Tree},
+    };
+
+    fn parse_trees(trees: &str) -> Vec<Tree> {
+        let text = format!(
+            "#NEXUS
+            BEGIN TAXA;
+                DIMENSIONS NTAX=6;
+                TAXLABELS A B C D E F;
+            END;
+            BEGIN TREES;
+                {}
+            END;",
+            trees
+        );
+        let lexer = Lexer::new(&text);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse().unwrap();
+        match nexus.blocks.into_iter().nth(1) {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    #[test]
+    fn test_robinson_foulds() {
+        let trees = parse_trees(
+            "TREE t1 = ((A,B),(C,(D,(E,F))));
+            TREE t2 = (A,(B,(C,(D,(E,F)))));
+            TREE t3 = ((A,C),(B,(D,(E,F))));
+            TREE t4 = ((A,F),(E,(D,(C,B))));",
+        );
+
+        // rerooting does not change the splits
+        assert_eq!(robinson_foulds(&trees[0], &trees[1], 6), 0);
+        assert_eq!(robinson_foulds(&trees[0], &trees[2], 6), 2);
+        assert_eq!(robinson_foulds(&trees[0], &trees[3], 6), 6);
+        assert_eq!(
+            normalized_robinson_foulds(&trees[0], &trees[2], 6),
+            2.0 / 6.0
+        );
+        assert_eq!(normalized_robinson_foulds(&trees[0], &trees[3], 6), 1.0);
+    }
+
+    #[test]
+    fn test_branch_lengths_distances() {
+        let trees = parse_trees(
+            "TREE t1 = ((A:1,B:1):1,(C:1,D:1):1);
+            TREE t2 = ((A:1,B:2):1,(C:1,D:1):3);
+            TREE t3 = ((A:1,C:1):2,(B:1,D:1):0);",
+        );
+
+        // (A,B)|(C,D) has length 2 in t1 and 4 in t2, B has length 1 and 2
+        assert_eq!(weighted_robinson_foulds(&trees[0], &trees[1], 6), 3.0);
+        assert_eq!(branch_score(&trees[0], &trees[1], 6), 5.0_f64.sqrt());
+        // the inner splits differ, the leaves have the same lengths
+        assert_eq!(weighted_robinson_foulds(&trees[0], &trees[2], 6), 4.0);
+        assert_eq!(branch_score(&trees[0], &trees[2], 6), 8.0_f64.sqrt());
+    }
+
+    #[test]
+    fn test_distance_matrix() {
+        let trees = parse_trees(
+            "TREE t1 = ((A,B),(C,(D,(E,F))));
+            TREE t2 = ((A,C),(B,(D,(E,F))));
+            TREE t3 = ((A,F),(E,(D,(C,B))));",
+        );
+        let matrix = distance_matrix(&trees, 6, TreeDistance::RobinsonFoulds);
+
+        assert_eq!(
+            matrix,
+            vec![
+                vec![0.0, 2.0, 6.0],
+                vec![2.0, 0.0, 6.0],
+                vec![6.0, 6.0, 0.0],
+            ]
+        );
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                assert_eq!(
+                    *value,
+                    TreeDistance::RobinsonFoulds.between(&trees[i], &trees[j], 6)
+                );
+            }
+        }
+        assert!(distance_matrix(&[], 6, TreeDistance::BranchScore).is_empty());
+    }
+}
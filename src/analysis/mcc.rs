@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    analysis::clade_counts::CladeCounts,
+    types::{
+        clades::Clade,
+        tree::{AttributeValue, Attributes, Tree, TreeNode},
+    },
+};
+
+/// How the credibility of a tree is computed from the frequencies of its clades.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CladeCredibility {
+    /// The product of the frequencies, computed as the sum of their logarithms.
+    Product,
+    /// The sum of the frequencies.
+    Sum,
+}
+
+/// The node heights of an annotated tree.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum NodeHeights {
+    /// The heights given by the branch lengths of the tree.
+    Keep,
+    /// The mean height of the clade of every node in the sample.
+    Mean,
+    /// The median height of the clade of every node in the sample.
+    Median,
+}
+
+/// The heights and attributes every clade had in the sample.
+#[derive(Debug, Default, Clone)]
+struct CladeSamples {
+    heights: Vec<f64>,
+    attributes: BTreeMap<String, Vec<AttributeValue>>,
+}
+
+/// Summarizes a sample of rooted trees, e.g. a BEAST posterior, the way BEAST's
+/// TreeAnnotator does: it picks the maximum clade credibility (MCC) tree and
+/// annotates its nodes with the posterior of their clades, their heights and the
+/// attributes of the clades in the sample.
+///
+/// Trees read one at a time from a [`TreeReader`](crate::TreeReader) are added in
+/// a first pass; a second pass over the file then finds the tree with the highest
+/// [`TreeAnnotator::credibility`].
+#[derive(Debug, Clone)]
+pub struct TreeAnnotator {
+    clade_counts: CladeCounts,
+    samples: HashMap<Clade, CladeSamples>,
+}
+
+/// The MCC tree of a sample of trees, annotated by a [`TreeAnnotator`].
+pub fn mcc_tree(
+    trees: &[Tree],
+    taxa_count: usize,
+    credibility: CladeCredibility,
+    heights: NodeHeights,
+) -> Option<Tree> {
+    let mut annotator = TreeAnnotator::new(taxa_count);
+    annotator.extend(trees);
+    annotator
+        .mcc_tree(trees, credibility)
+        .map(|tree| annotator.annotate(tree, heights))
+}
+
+impl TreeAnnotator {
+    /// `taxa_count` is the number of taxa of the file the trees belong to.
+    pub fn new(taxa_count: usize) -> Self {
+        Self {
+            clade_counts: CladeCounts::new(taxa_count),
+            samples: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, tree: &Tree) {
+        self.clade_counts.add(tree);
+
+        let heights = tree.node_heights();
+        for (node_id, clade) in tree.node_clades(self.clade_counts.taxa_count()) {
+            let samples = self.samples.entry(clade).or_default();
+            samples.heights.push(heights[&node_id]);
+            for (key, value) in tree.node(node_id).attributes() {
+                samples
+                    .attributes
+                    .entry(key.clone())
+                    .or_default()
+                    .push(value.clone());
+            }
+        }
+    }
+
+    /// The clades counted so far.
+    pub fn clade_counts(&self) -> &CladeCounts {
+        &self.clade_counts
+    }
+
+    /// The credibility of a tree, computed from the frequencies of the clades of
+    /// its internal nodes other than the root.
+    pub fn credibility(&self, tree: &Tree, credibility: CladeCredibility) -> f64 {
+        let mut node_clades = tree.node_clades(self.clade_counts.taxa_count());
+        node_clades.pop();
+
+        let frequencies = node_clades
+            .iter()
+            .filter(|(node_id, _)| !tree.is_leaf(*node_id))
+            .map(|(_, clade)| self.clade_counts.frequency(clade));
+        match credibility {
+            CladeCredibility::Product => frequencies.map(f64::ln).sum(),
+            CladeCredibility::Sum => frequencies.sum(),
+        }
+    }
+
+    /// The tree with the highest credibility, the first one if several have it.
+    pub fn mcc_tree<'t>(
+        &self,
+        trees: impl IntoIterator<Item = &'t Tree>,
+        credibility: CladeCredibility,
+    ) -> Option<&'t Tree> {
+        let mut best: Option<(&Tree, f64)> = None;
+
+        for tree in trees {
+            let score = self.credibility(tree, credibility);
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((tree, score));
+            }
+        }
+
+        best.map(|(tree, _)| tree)
+    }
+
+    /// Annotates a rooted tree, usually the MCC tree, with TreeAnnotator's
+    /// attributes. Every internal node gets the `posterior` of its clade, and every
+    /// node whose clade is in the sample its mean `height` together with
+    /// `height_median`, `height_95%_HPD` and `height_range`. The attributes of the
+    /// clades in the sample are aggregated the same way if they are numbers; text
+    /// attributes are set to their most frequent value, with all values in
+    /// `<key>.set` and their frequencies in `<key>.set.prob`. Lists are dropped.
+    ///
+    /// With [`NodeHeights::Mean`] or [`NodeHeights::Median`], branch lengths are
+    /// recomputed from the summarized heights, so they may become negative.
+    pub fn annotate(&self, tree: &Tree, heights: NodeHeights) -> Tree {
+        let mut annotated = tree.clone();
+        annotated.rooted = true;
+
+        let node_clades = tree.node_clades(self.clade_counts.taxa_count());
+        let root = node_clades.last().map(|(node_id, _)| *node_id);
+        let mut summarized_heights = tree.node_heights();
+
+        for (node_id, clade) in node_clades {
+            let mut attributes = Attributes::new();
+
+            if let Some(samples) = self.samples.get(&clade) {
+                for (key, values) in &samples.attributes {
+                    summarize_values(key, values, &mut attributes);
+                }
+
+                let summary = Summary::new(samples.heights.clone());
+                summary.annotate("height", &mut attributes);
+                match heights {
+                    NodeHeights::Keep => {}
+                    NodeHeights::Mean => {
+                        summarized_heights.insert(node_id, summary.mean);
+                    }
+                    NodeHeights::Median => {
+                        summarized_heights.insert(node_id, summary.median);
+                    }
+                }
+            }
+
+            if !tree.is_leaf(node_id) {
+                let posterior = match Some(node_id) == root {
+                    true => 1.0,
+                    false => self.clade_counts.frequency(&clade),
+                };
+                attributes.insert("posterior".to_string(), AttributeValue::Number(posterior));
+            }
+
+            *annotated.tree[node_id].get_mut().attributes_mut() = attributes;
+        }
+
+        if heights != NodeHeights::Keep {
+            for node_id in tree.preorder() {
+                let Some(parent) = tree.parent(node_id) else {
+                    continue;
+                };
+                if let TreeNode::Leaf { length, .. } | TreeNode::InternalNode { length, .. } =
+                    annotated.tree[node_id].get_mut()
+                {
+                    *length = Some(summarized_heights[&parent] - summarized_heights[&node_id]);
+                }
+            }
+        }
+
+        annotated
+    }
+}
+
+impl<'t> Extend<&'t Tree> for TreeAnnotator {
+    fn extend<I: IntoIterator<Item = &'t Tree>>(&mut self, trees: I) {
+        for tree in trees {
+            self.add(tree);
+        }
+    }
+}
+
+/// The mean, median, 95% highest posterior density interval and range of a
+/// sample of numbers.
+#[derive(PartialEq, Debug)]
+struct Summary {
+    mean: f64,
+    median: f64,
+    hpd: (f64, f64),
+    range: (f64, f64),
+}
+
+impl Summary {
+    fn new(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        let count = values.len();
+
+        let median = match count % 2 {
+            0 => (values[count / 2 - 1] + values[count / 2]) / 2.0,
+            _ => values[count / 2],
+        };
+
+        // the shortest interval containing 95% of the values, as in BEAST
+        let window = ((0.95 * count as f64).round() as usize).max(1);
+        let hpd = (0..=count - window)
+            .map(|start| (values[start], values[start + window - 1]))
+            .min_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+            .unwrap_or_default();
+
+        Summary {
+            mean: values.iter().sum::<f64>() / count as f64,
+            median,
+            hpd,
+            range: (values[0], values[count - 1]),
+        }
+    }
+
+    fn annotate(&self, key: &str, attributes: &mut Attributes) {
+        let interval = |(low, high)| {
+            AttributeValue::List(vec![
+                AttributeValue::Number(low),
+                AttributeValue::Number(high),
+            ])
+        };
+
+        attributes.insert(key.to_string(), AttributeValue::Number(self.mean));
+        attributes.insert(
+            format!("{}_median", key),
+            AttributeValue::Number(self.median),
+        );
+        attributes.insert(format!("{}_95%_HPD", key), interval(self.hpd));
+        attributes.insert(format!("{}_range", key), interval(self.range));
+    }
+}
+
+fn summarize_values(key: &str, values: &[AttributeValue], attributes: &mut Attributes) {
+    let numbers: Option<Vec<f64>> = values
+        .iter()
+        .map(|value| match value {
+            AttributeValue::Number(number) => Some(*number),
+            _ => None,
+        })
+        .collect();
+    if let Some(numbers) = numbers {
+        Summary::new(numbers).annotate(key, attributes);
+        return;
+    }
+
+    let mut counts: Vec<(&str, usize)> = vec![];
+    for value in values {
+        let AttributeValue::Text(text) = value else {
+            return;
+        };
+        match counts.iter_mut().find(|(other, _)| other == text) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((text, 1)),
+        }
+    }
+    // the most frequent values first, in the order they were seen otherwise
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    let text = |text: &str| AttributeValue::Text(text.to_string());
+    attributes.insert(key.to_string(), text(counts[0].0));
+    attributes.insert(
+        format!("{}.set", key),
+        AttributeValue::List(counts.iter().map(|(value, _)| text(value)).collect()),
+    );
+    attributes.insert(
+        format!("{}.set.prob", key),
+        AttributeValue::List(
+            counts
+                .iter()
+                .map(|(_, count)| AttributeValue::Number(*count as f64 / values.len() as f64))
+                .collect(),
+        ),
+    );
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::mcc::{CladeCredibility, NodeHeights, TreeAnnotator, mcc_tree},
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{
+            nexus::{Nexus, NexusBlock},
+            tree::{AttributeValue, Tree},
+        },
+        writer::newick::write_newick,
+    };
+
+    const POSTERIOR: &str = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=4;
+            TAXLABELS A B C D;
+        END;
+        BEGIN TREES;
+            TREE t1 = [&R] ((A[&rate=1]:1,B:1):1,(C:1.5,D:1.5):0.5)[&state=x];
+            TREE t2 = [&R] ((A[&rate=3]:2,B:2):1,(C:1,D:1):2)[&state=y];
+            TREE t3 = [&R] (((A[&rate=2]:1,B:1):1,C:2):1,D:3)[&state=x];
+            TREE t4 = [&R] ((A[&rate=2]:1,C:1):2,(B:2,D:2):1)[&state=x];
+        END;";
+
+    fn parse(text: &str) -> Nexus {
+        let lexer = Lexer::new(text);
+        Parser::new(Tokens::new(&lexer)).parse().unwrap()
+    }
+
+    fn trees(nexus: &Nexus) -> &[Tree] {
+        match nexus.blocks.get(1) {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    fn number(value: Option<&AttributeValue>) -> f64 {
+        match value {
+            Some(AttributeValue::Number(number)) => *number,
+            value => panic!("Expected a number, got {:?}.", value),
+        }
+    }
+
+    fn interval(low: f64, high: f64) -> AttributeValue {
+        AttributeValue::List(vec![
+            AttributeValue::Number(low),
+            AttributeValue::Number(high),
+        ])
+    }
+
+    #[test]
+    fn test_credibility() {
+        let nexus = parse(POSTERIOR);
+        let trees = trees(&nexus);
+        let mut annotator = TreeAnnotator::new(4);
+        annotator.extend(trees);
+
+        assert_eq!(annotator.clade_counts().tree_count(), 4);
+        assert_eq!(
+            annotator.credibility(&trees[0], CladeCredibility::Product),
+            0.75f64.ln() + 0.5f64.ln()
+        );
+        assert_eq!(
+            annotator.credibility(&trees[2], CladeCredibility::Sum),
+            0.75 + 0.25
+        );
+
+        // t1 and t2 share the best topology, and the first one is picked
+        let mcc = annotator.mcc_tree(trees, CladeCredibility::Product);
+        assert_eq!(mcc.map(|tree| tree.name.as_str()), Some("t1"));
+        let mcc = annotator.mcc_tree(trees, CladeCredibility::Sum);
+        assert_eq!(mcc.map(|tree| tree.name.as_str()), Some("t1"));
+    }
+
+    #[test]
+    fn test_annotated_heights_and_attributes() {
+        let nexus = parse(POSTERIOR);
+        let mcc = mcc_tree(
+            trees(&nexus),
+            4,
+            CladeCredibility::Product,
+            NodeHeights::Keep,
+        )
+        .unwrap();
+
+        let root = mcc.node(mcc.root().unwrap()).attributes();
+        assert_eq!(number(root.get("posterior")), 1.0);
+        assert_eq!(number(root.get("height")), 2.75);
+        assert_eq!(number(root.get("height_median")), 3.0);
+        assert_eq!(root.get("height_95%_HPD"), Some(&interval(2.0, 3.0)));
+        assert_eq!(root.get("height_range"), Some(&interval(2.0, 3.0)));
+        assert_eq!(root.get("state"), Some(&AttributeValue::Text("x".into())));
+        assert_eq!(
+            root.get("state.set"),
+            Some(&AttributeValue::List(vec![
+                AttributeValue::Text("x".into()),
+                AttributeValue::Text("y".into())
+            ]))
+        );
+        assert_eq!(root.get("state.set.prob"), Some(&interval(0.75, 0.25)));
+
+        let a = mcc.leaf(0).unwrap();
+        assert_eq!(number(mcc.node(a).attributes().get("rate")), 2.0);
+        assert_eq!(
+            mcc.node(a).attributes().get("rate_range"),
+            Some(&interval(1.0, 3.0))
+        );
+        assert_eq!(mcc.node(a).attributes().get("posterior"), None);
+        // the heights of the tree are kept
+        assert_eq!(mcc.node(a).length(), Some(1.0));
+
+        let a_b = mcc.parent(a).unwrap();
+        assert_eq!(number(mcc.node(a_b).attributes().get("posterior")), 0.75);
+        assert_eq!(number(mcc.node(a_b).attributes().get("height_median")), 1.0);
+
+        let newick = write_newick(&mcc, &nexus.taxa);
+        assert!(newick.starts_with("[&R] ((A[&height=0,"));
+        assert!(newick.ends_with(
+            ")[&height=2.75,height_95%_HPD={2,3},height_median=3,height_range={2,3},\
+            posterior=1,state=x,state.set={x,y},state.set.prob={0.75,0.25}];"
+        ));
+    }
+
+    #[test]
+    fn test_summarized_node_heights() {
+        let nexus = parse(POSTERIOR);
+        let trees = trees(&nexus);
+
+        let mean = mcc_tree(trees, 4, CladeCredibility::Product, NodeHeights::Mean).unwrap();
+        let a = mean.leaf(0).unwrap();
+        let a_b = mean.parent(a).unwrap();
+        assert_eq!(mean.node(a).length(), Some(4.0 / 3.0));
+        assert_eq!(mean.node(a_b).length(), Some(2.75 - 4.0 / 3.0));
+        assert!(mean.is_ultrametric(1e-12));
+
+        let median = mcc_tree(trees, 4, CladeCredibility::Product, NodeHeights::Median).unwrap();
+        let heights = median.node_heights();
+        assert_eq!(heights[&median.root().unwrap()], 3.0);
+        assert_eq!(
+            heights[&median.parent(median.leaf(2).unwrap()).unwrap()],
+            1.25
+        );
+    }
+}
@@ -0,0 +1,22 @@
+pub mod clade_counts;
+pub mod consensus;
+mod consensus_tests;
+pub mod convergence;
+mod convergence_tests;
+pub mod distances;
+mod distances_tests;
+pub mod mcc;
+mod mcc_tests;
+pub mod patristic;
+mod patristic_tests;
+mod quartets;
+mod quartets_tests;
+pub mod statistics;
+mod statistics_tests;
+pub mod support;
+mod support_tests;
+pub mod topologies;
+mod topologies_tests;
+pub mod tree_building;
+mod tree_building_tests;
+mod triplets;
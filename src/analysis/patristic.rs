@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use indextree::NodeId;
+
+use crate::types::{taxa::TaxonId, tree::Tree};
+
+impl Tree {
+    /// The matrix of the path lengths between all pairs of leaves, indexed by
+    /// [`TaxonId`], i.e. in TAXA block order. `taxa_count` is the number of taxa of
+    /// the file; the rows and columns of taxa that are not in the tree are NaN.
+    /// Missing lengths count as 0.
+    ///
+    /// Every pair of leaves is visited once, at its most recent common ancestor, so
+    /// this takes O(n²) time for n leaves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a leaf refers to a taxon not below `taxa_count`.
+    pub fn patristic_distances(&self, taxa_count: usize) -> Vec<Vec<f64>> {
+        let mut distances = vec![vec![f64::NAN; taxa_count]; taxa_count];
+        // the leaves below every node with their distance to it
+        let mut below: HashMap<NodeId, Vec<(TaxonId, f64)>> = HashMap::new();
+
+        for node_id in self.postorder() {
+            let mut leaves = vec![];
+            if let Some(taxon) = self.node(node_id).taxon() {
+                distances[taxon][taxon] = 0.0;
+                leaves.push((taxon, 0.0));
+            }
+
+            for child in self.children(node_id) {
+                let length = self.node(child).length().unwrap_or_default();
+                let child_leaves = below.remove(&child).unwrap_or_default();
+                for (a, a_distance) in &leaves {
+                    for (b, b_distance) in &child_leaves {
+                        let distance = a_distance + b_distance + length;
+                        distances[*a][*b] = distance;
+                        distances[*b][*a] = distance;
+                    }
+                }
+                leaves.extend(
+                    child_leaves
+                        .into_iter()
+                        .map(|(taxon, distance)| (taxon, distance + length)),
+                );
+            }
+
+            below.insert(node_id, leaves);
+        }
+
+        distances
+    }
+}
+
+/// The mean of the patristic distances (see [`Tree::patristic_distances`]) of a
+/// sample of trees, e.g. of a posterior read one tree at a time with a
+/// [`TreeReader`](crate::TreeReader), or `None` if there are no trees. Pairs of
+/// taxa that are not both in every tree are NaN.
+pub fn mean_patristic_distances<'t>(
+    trees: impl IntoIterator<Item = &'t Tree>,
+    taxa_count: usize,
+) -> Option<Vec<Vec<f64>>> {
+    let mut sums = vec![vec![0.0; taxa_count]; taxa_count];
+    let mut tree_count = 0;
+
+    for tree in trees {
+        tree_count += 1;
+        for (sum_row, row) in sums.iter_mut().zip(tree.patristic_distances(taxa_count)) {
+            for (sum, distance) in sum_row.iter_mut().zip(row) {
+                *sum += distance;
+            }
+        }
+    }
+
+    (tree_count > 0).then(|| {
+        sums.into_iter()
+            .map(|row| row.into_iter().map(|sum| sum / tree_count as f64).collect())
+            .collect()
+    })
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::patristic::mean_patristic_distances,
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{nexus::NexusBlock, tree::Tree},
+    };
+
+    fn parse_trees(newick: &str) -> Vec<Tree> {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        match nexus.blocks.into_iter().next() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    #[test]
+    fn test_patristic_distances() {
+        let trees = parse_trees("((A:1,B:2):1,(C:3,D:1):0.5,E);");
+        let distances = trees[0].patristic_distances(5);
+
+        assert_eq!(
+            distances,
+            vec![
+                vec![0.0, 3.0, 5.5, 3.5, 2.0],
+                vec![3.0, 0.0, 6.5, 4.5, 3.0],
+                vec![5.5, 6.5, 0.0, 4.0, 3.5],
+                vec![3.5, 4.5, 4.0, 0.0, 1.5],
+                vec![2.0, 3.0, 3.5, 1.5, 0.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_taxa_missing_from_the_tree() {
+        // the second tree has no C, which is taxon 2
+        let trees = parse_trees("((A:1,B:2):1,C:3);((A:1,B:1):1,D:1);");
+        let distances = trees[1].patristic_distances(4);
+
+        assert_eq!(distances[0][1], 2.0);
+        assert_eq!(distances[0][3], 3.0);
+        assert!(distances[2].iter().all(|distance| distance.is_nan()));
+        assert!(distances[0][2].is_nan());
+    }
+
+    #[test]
+    fn test_mean_patristic_distances() {
+        let trees = parse_trees("((A:1,B:2):1,C:3);((A:1,C:1):1,B:1);");
+        let mean = mean_patristic_distances(&trees, 3).unwrap();
+
+        assert_eq!(
+            mean,
+            vec![
+                vec![0.0, 3.0, 3.5],
+                vec![3.0, 0.0, 4.5],
+                vec![3.5, 4.5, 0.0],
+            ]
+        );
+        assert_eq!(mean_patristic_distances(&[], 3), None);
+    }
+}
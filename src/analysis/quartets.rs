@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use indextree::NodeId;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::types::{taxa::TaxonId, tree::Tree};
+
+/// The nodes of a tree indexed by their position in postorder, so that the root
+/// is the last one.
+pub(crate) struct TreeNodes {
+    pub(super) children: Vec<Vec<usize>>,
+    pub(super) parents: Vec<Option<usize>>,
+    pub(super) taxa: Vec<Option<TaxonId>>,
+    pub(super) leaves: HashMap<TaxonId, usize>,
+}
+
+impl TreeNodes {
+    pub(crate) fn new(tree: &Tree) -> Self {
+        let postorder: Vec<NodeId> = tree.postorder().collect();
+        let indices: HashMap<NodeId, usize> = postorder
+            .iter()
+            .enumerate()
+            .map(|(index, node_id)| (*node_id, index))
+            .collect();
+
+        let taxa: Vec<Option<TaxonId>> = postorder
+            .iter()
+            .map(|node_id| tree.node(*node_id).taxon())
+            .collect();
+        Self {
+            children: postorder
+                .iter()
+                .map(|node_id| {
+                    tree.children(*node_id)
+                        .map(|child| indices[&child])
+                        .collect()
+                })
+                .collect(),
+            parents: postorder
+                .iter()
+                .map(|node_id| tree.parent(*node_id).map(|parent| indices[&parent]))
+                .collect(),
+            leaves: taxa
+                .iter()
+                .enumerate()
+                .filter_map(|(index, taxon)| Some(((*taxon)?, index)))
+                .collect(),
+            taxa,
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub(super) fn root(&self) -> usize {
+        self.len() - 1
+    }
+
+    /// The subtrees around a node of the tree seen as unrooted: the clades of its
+    /// children and, unless it is the root, all other taxa.
+    fn subtrees(&self, node: usize) -> Vec<Subtree> {
+        let mut subtrees: Vec<Subtree> = self.children[node]
+            .iter()
+            .map(|child| Subtree::Below(*child))
+            .collect();
+        if self.parents[node].is_some() {
+            subtrees.push(Subtree::Above(node));
+        }
+        subtrees
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Subtree {
+    /// The taxa below a node.
+    Below(usize),
+    /// The taxa not below a node.
+    Above(usize),
+}
+
+/// The number of leaves of every node whose taxon is in the other tree.
+pub(super) fn shared_leaves(nodes: &TreeNodes, other: &TreeNodes) -> Vec<i64> {
+    let mut shared = vec![0; nodes.len()];
+    for node in 0..nodes.len() {
+        shared[node] = match nodes.taxa[node] {
+            Some(taxon) => i64::from(other.leaves.contains_key(&taxon)),
+            None => nodes.children[node]
+                .iter()
+                .map(|child| shared[*child])
+                .sum(),
+        };
+    }
+    shared
+}
+
+/// The number of pairs of taxa in different sets, given the sizes of the sets.
+pub(super) fn pairs(sizes: impl IntoIterator<Item = i64>) -> i64 {
+    let (sum, squares) = sizes.into_iter().fold((0, 0), |(sum, squares), size| {
+        (sum + size, squares + size * size)
+    });
+    (sum * sum - squares) / 2
+}
+
+fn choose_2(n: i64) -> i64 {
+    n * (n - 1) / 2
+}
+
+/// The number of quartets of taxa with a different topology in the two trees,
+/// seen as unrooted: resolved differently, or resolved in one and a star in the
+/// other. See [`TreeDistance::Quartet`](crate::TreeDistance::Quartet).
+pub(crate) fn different_quartets(a: &TreeNodes, b: &TreeNodes) -> usize {
+    if a.len() == 0 || b.len() == 0 {
+        return 0;
+    }
+    let (shared_a, shared_b) = (shared_leaves(a, b), shared_leaves(b, a));
+    let taxa = shared_a[a.root()];
+
+    // every resolved quartet ab|cd is seen from both of its sides: at the node
+    // where a and b are in different subtrees and c and d in a third one
+    let resolved = |shared: &[i64], subtrees: &[Subtree]| -> i64 {
+        let sizes: Vec<i64> = subtrees
+            .iter()
+            .map(|subtree| match subtree {
+                Subtree::Below(child) => shared[*child],
+                Subtree::Above(node) => taxa - shared[*node],
+            })
+            .collect();
+        (0..sizes.len())
+            .map(|e| {
+                let others = sizes.iter().enumerate().filter(|(i, _)| *i != e);
+                pairs(others.map(|(_, size)| *size)) * choose_2(sizes[e])
+            })
+            .sum()
+    };
+    let nodes_a: Vec<usize> = (0..a.len())
+        .filter(|node| a.subtrees(*node).len() >= 3)
+        .collect();
+    let nodes_b: Vec<Vec<Subtree>> = (0..b.len())
+        .map(|node| b.subtrees(node))
+        .filter(|subtrees| subtrees.len() >= 3)
+        .collect();
+    let resolved_a: i64 = nodes_a
+        .iter()
+        .map(|node| resolved(&shared_a, &a.subtrees(*node)))
+        .sum();
+    let resolved_b: i64 = nodes_b
+        .iter()
+        .map(|subtrees| resolved(&shared_b, subtrees))
+        .sum();
+
+    let mut firsts: Vec<usize> = (0..a.len()).collect();
+    for node in 0..a.len() {
+        if let Some(child) = a.children[node].first() {
+            firsts[node] = firsts[*child];
+        }
+    }
+    let trees = Trees {
+        a,
+        b,
+        partners: a
+            .taxa
+            .iter()
+            .map(|taxon| b.leaves.get(&(*taxon)?).copied())
+            .collect(),
+        firsts,
+        nodes_b,
+    };
+    #[cfg(feature = "parallel")]
+    let counts: Vec<(i64, i64)> = nodes_a
+        .par_iter()
+        .map_init(QuartetCounter::default, |counter, node| {
+            counter.count_node(&trees, *node)
+        })
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let counts: Vec<(i64, i64)> = {
+        let mut counter = QuartetCounter::default();
+        nodes_a
+            .iter()
+            .map(|node| counter.count_node(&trees, *node))
+            .collect()
+    };
+    let (shared, different) = counts
+        .into_iter()
+        .fold((0, 0), |(shared, different), counts| {
+            (shared + counts.0, different + counts.1)
+        });
+
+    // shared quartets are counted from both sides, different ones from both sides
+    // in both trees; the ones resolved in both trees are counted in both of them
+    let distance = resolved_a / 2 + resolved_b / 2 - shared - different / 4;
+    distance as usize
+}
+
+/// The two trees compared by [`different_quartets`].
+struct Trees<'t> {
+    a: &'t TreeNodes,
+    b: &'t TreeNodes,
+    /// The leaf of the second tree with the taxon of every leaf of the first one.
+    partners: Vec<Option<usize>>,
+    /// The first node of the subtree of every node of the first tree in postorder.
+    firsts: Vec<usize>,
+    /// The subtrees around the nodes of the second tree with at least three of them.
+    nodes_b: Vec<Vec<Subtree>>,
+}
+
+/// Counts the quartets ab|cd seen from pairs of nodes in both trees (see
+/// [`different_quartets`]), reusing its buffers for all pairs.
+#[derive(Default)]
+struct QuartetCounter {
+    /// For a node of the first tree with r subtrees around it, counts[v·r + i] is
+    /// the number of taxa below the node v of the second tree which are in the
+    /// i-th subtree.
+    counts: Vec<i64>,
+    /// For a pair of nodes, m[i][j] is the number of taxa in the i-th subtree around
+    /// the first node and the j-th subtree around the second one, row by row.
+    m: Vec<i64>,
+    cols: usize,
+    row_sums: Vec<i64>,
+    col_sums: Vec<i64>,
+    row_squares: Vec<i64>,
+    col_squares: Vec<i64>,
+    paths: Vec<i64>,
+}
+
+impl QuartetCounter {
+    fn m(&self, i: usize, j: usize) -> i64 {
+        self.m[i * self.cols + j]
+    }
+
+    /// Counts the quartets seen from a node of the first tree and every node of the
+    /// second one, as the number of quartets resolved the same way and the number
+    /// of quartets resolved differently (see [`QuartetCounter::count`]). The taxa
+    /// of every subtree around the node are counted below every node of the second
+    /// tree in O(r·n) time and memory for r subtrees.
+    fn count_node(&mut self, trees: &Trees, node: usize) -> (i64, i64) {
+        let (a, b) = (trees.a, trees.b);
+        let rows = a.subtrees(node);
+        let r = rows.len();
+        self.counts.clear();
+        self.counts.resize(b.len() * r, 0);
+
+        for (i, row) in rows.iter().enumerate() {
+            let nodes = match *row {
+                Subtree::Below(child) => trees.firsts[child]..child + 1,
+                Subtree::Above(_) => 0..0,
+            };
+            for descendant in nodes {
+                if let Some(leaf) = trees.partners[descendant] {
+                    self.counts[leaf * r + i] += 1;
+                }
+            }
+        }
+        if let Some(Subtree::Above(_)) = rows.last() {
+            let outside = (0..trees.firsts[node]).chain(node + 1..a.len());
+            for other in outside {
+                if let Some(leaf) = trees.partners[other] {
+                    self.counts[leaf * r + r - 1] += 1;
+                }
+            }
+        }
+        for other in 0..b.len() {
+            for child in &b.children[other] {
+                for i in 0..r {
+                    self.counts[other * r + i] += self.counts[child * r + i];
+                }
+            }
+        }
+
+        let (mut shared, mut different) = (0, 0);
+        for cols in &trees.nodes_b {
+            self.m.clear();
+            for i in 0..r {
+                for col in cols {
+                    self.m.push(match *col {
+                        Subtree::Below(child) => self.counts[child * r + i],
+                        Subtree::Above(other) => {
+                            self.counts[b.root() * r + i] - self.counts[other * r + i]
+                        }
+                    });
+                }
+            }
+            let counts = self.count(r, cols.len());
+            shared += counts.0;
+            different += counts.1;
+        }
+        (shared, different)
+    }
+
+    /// Counts the quartets seen from a pair of nodes, with a and b in different
+    /// rows and columns and c and d in one cell: the ones resolved the same way,
+    /// and the ones resolved as ac|bd in the second tree, i.e. with c in the row of
+    /// d and b in the column of d. This takes O(r²c) time for r rows and c columns.
+    fn count(&mut self, rows: usize, cols: usize) -> (i64, i64) {
+        self.cols = cols;
+        let rows = 0..rows;
+        let cols = 0..cols;
+        let (mut shared, mut different) = (0, 0);
+
+        self.row_sums.clear();
+        self.row_squares.clear();
+        for i in rows.clone() {
+            let row = &self.m[i * self.cols..(i + 1) * self.cols];
+            self.row_sums.push(row.iter().sum());
+            self.row_squares.push(row.iter().map(|x| x * x).sum());
+        }
+        self.col_sums.clear();
+        self.col_squares.clear();
+        for j in cols.clone() {
+            self.col_sums.push(rows.clone().map(|i| self.m(i, j)).sum());
+            self.col_squares
+                .push(rows.clone().map(|i| self.m(i, j) * self.m(i, j)).sum());
+        }
+        // a and b need two rows and two columns
+        if self.row_sums.iter().filter(|sum| **sum > 0).count() < 2
+            || self.col_sums.iter().filter(|sum| **sum > 0).count() < 2
+        {
+            return (0, 0);
+        }
+        let total: i64 = self.row_sums.iter().sum();
+        let squares: i64 = self.row_squares.iter().sum();
+
+        for e in rows.clone() {
+            // paths[i] is the sum over j of m[i][j] * m[e][j]
+            self.paths.clear();
+            for i in rows.clone() {
+                self.paths
+                    .push(cols.clone().map(|j| self.m(i, j) * self.m(e, j)).sum());
+            }
+
+            for f in cols.clone() {
+                let m_ef = self.m(e, f);
+                if m_ef == 0 {
+                    continue;
+                }
+                let (row_sums, col_sums) = (&self.row_sums, &self.col_sums);
+
+                // pairs in different rows other than e and columns other than f
+                let rest = total - row_sums[e] - col_sums[f] + m_ef;
+                let rest_rows: i64 = rows
+                    .clone()
+                    .filter(|i| *i != e)
+                    .map(|i| (row_sums[i] - self.m(i, f)).pow(2))
+                    .sum();
+                let rest_cols: i64 = cols
+                    .clone()
+                    .filter(|j| *j != f)
+                    .map(|j| (col_sums[j] - self.m(e, j)).pow(2))
+                    .sum();
+                let rest_squares =
+                    squares - self.row_squares[e] - self.col_squares[f] + m_ef * m_ef;
+                let rest_pairs = (rest * rest - rest_rows - rest_cols + rest_squares) / 2;
+                shared += rest_pairs * choose_2(m_ef);
+
+                // the sum over a in cell (i, j), i != e and j != f, of the choices of
+                // b in column f outside rows e and i, and of c in row e outside
+                // columns f and j
+                let in_col = col_sums[f] - m_ef;
+                let in_row = row_sums[e] - m_ef;
+                let with_row: i64 = cols
+                    .clone()
+                    .filter(|j| *j != f)
+                    .map(|j| self.m(e, j) * (col_sums[j] - self.m(e, j)))
+                    .sum();
+                let with_col: i64 = rows
+                    .clone()
+                    .filter(|i| *i != e)
+                    .map(|i| self.m(i, f) * (row_sums[i] - self.m(i, f)))
+                    .sum();
+                let with_both = rows
+                    .clone()
+                    .map(|i| self.m(i, f) * self.paths[i])
+                    .sum::<i64>()
+                    - m_ef * (self.row_squares[e] + self.col_squares[f])
+                    + m_ef.pow(3);
+                different += m_ef
+                    * (in_col * in_row * rest - in_col * with_row - in_row * with_col + with_both);
+            }
+        }
+        (shared, different)
+    }
+}
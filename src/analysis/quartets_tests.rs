@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::distances::{TreeDistance, distance_matrix, quartet_distance, triplet_distance},
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{clades::Clade, nexus::NexusBlock, tree::Tree},
+    };
+
+    fn parse_trees(newick: &str) -> Vec<Tree> {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        match nexus.blocks.into_iter().next() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    /// The sets of taxa of a tree splitting the given taxa into the returned ones
+    /// and the others, or `None` if none does.
+    fn resolution(sets: &[Clade], taxa: &[usize], size: usize) -> Option<Vec<usize>> {
+        sets.iter().find_map(|set| {
+            let inside: Vec<usize> = taxa.iter().copied().filter(|t| set.contains(*t)).collect();
+            (inside.len() == size).then_some(inside)
+        })
+    }
+
+    fn naive_quartet_distance(a: &Tree, b: &Tree, taxa_count: usize) -> usize {
+        naive_quartet_distance_of(a, b, taxa_count, &(0..taxa_count).collect::<Vec<_>>())
+    }
+
+    /// Compares the topologies of all quartets of the given taxa, with splits
+    /// normalized to the side without the first of the taxa.
+    fn naive_quartet_distance_of(a: &Tree, b: &Tree, taxa_count: usize, taxa: &[usize]) -> usize {
+        let normalized = |tree: &Tree, taxa: &[usize]| {
+            let mut splits = tree.splits(taxa_count);
+            for split in &mut splits {
+                if split.contains(taxa[0]) {
+                    split.toggle_range(..);
+                }
+            }
+            resolution(&splits, taxa, 2)
+        };
+
+        let mut distance = 0;
+        for w in 0..taxa.len() {
+            for x in w + 1..taxa.len() {
+                for y in x + 1..taxa.len() {
+                    for z in y + 1..taxa.len() {
+                        let taxa = [taxa[w], taxa[x], taxa[y], taxa[z]];
+                        if normalized(a, &taxa) != normalized(b, &taxa) {
+                            distance += 1;
+                        }
+                    }
+                }
+            }
+        }
+        distance
+    }
+
+    fn naive_triplet_distance(a: &Tree, b: &Tree, taxa_count: usize) -> usize {
+        naive_triplet_distance_of(a, b, taxa_count, &(0..taxa_count).collect::<Vec<_>>())
+    }
+
+    fn naive_triplet_distance_of(a: &Tree, b: &Tree, taxa_count: usize, taxa: &[usize]) -> usize {
+        let (clades_a, clades_b) = (a.clades(taxa_count), b.clades(taxa_count));
+
+        let mut distance = 0;
+        for x in 0..taxa.len() {
+            for y in x + 1..taxa.len() {
+                for z in y + 1..taxa.len() {
+                    let taxa = [taxa[x], taxa[y], taxa[z]];
+                    if resolution(&clades_a, &taxa, 2) != resolution(&clades_b, &taxa, 2) {
+                        distance += 1;
+                    }
+                }
+            }
+        }
+        distance
+    }
+
+    // binary and multifurcating trees on the same nine taxa
+    const TREES: &str = "(((A,B),(C,D)),((E,F),(G,(H,I))));
+        ((A,(B,(C,(D,(E,(F,(G,(H,I)))))))));
+        ((A,C),(B,D),(E,F,G),(H,I));
+        (A,B,C,D,E,F,G,H,I);
+        ((I,H),((G,F),(E,(D,(A,B,C)))));
+        (((A,B,C,D),(E,F)),((G,H),I));
+        ((B,(A,(C,D))),(E,(H,(F,G,I))));";
+
+    #[test]
+    fn test_quartet_distance() {
+        let trees = parse_trees(TREES);
+
+        for a in &trees {
+            for b in &trees {
+                assert_eq!(
+                    quartet_distance(a, b, 9),
+                    naive_quartet_distance(a, b, 9),
+                    "{} and {}",
+                    a.name,
+                    b.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_triplet_distance() {
+        let trees = parse_trees(TREES);
+
+        for a in &trees {
+            for b in &trees {
+                assert_eq!(
+                    triplet_distance(a, b, 9),
+                    naive_triplet_distance(a, b, 9),
+                    "{} and {}",
+                    a.name,
+                    b.name
+                );
+            }
+        }
+    }
+
+    /// A random multifurcating tree on some of the taxa A to L, as Newick.
+    fn random_tree(random: &mut impl FnMut() -> usize) -> String {
+        let mut subtrees: Vec<String> = "ABCDEFGHIJKL"
+            .chars()
+            .filter(|_| !random().is_multiple_of(6))
+            .map(String::from)
+            .collect();
+        while subtrees.len() > 1 {
+            let degree = (2 + random() % 3).min(subtrees.len());
+            let children: Vec<String> = (0..degree)
+                .map(|_| subtrees.swap_remove(random() % subtrees.len()))
+                .collect();
+            subtrees.push(format!("({})", children.join(",")));
+        }
+        format!("{};", subtrees.concat())
+    }
+
+    #[test]
+    fn test_random_trees() {
+        let mut seed: u64 = 7;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as usize
+        };
+
+        for _ in 0..50 {
+            let newick = format!("{}{}", random_tree(&mut random), random_tree(&mut random));
+            let lexer = Lexer::new(&newick);
+            let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+            let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.first() else {
+                panic!("Expected a trees block.");
+            };
+            let (a, b) = (&trees[0], &trees[1]);
+            let taxa_count = nexus.taxa.len();
+
+            // taxa missing from one of the trees are left out of the naive counts
+            let taxa: Vec<usize> = a
+                .leaves()
+                .filter_map(|leaf| a.node(leaf).taxon())
+                .filter(|taxon| b.leaves().any(|leaf| b.node(leaf).taxon() == Some(*taxon)))
+                .collect();
+
+            assert_eq!(
+                triplet_distance(a, b, taxa_count),
+                naive_triplet_distance_of(a, b, taxa_count, &taxa),
+                "{}",
+                newick
+            );
+            assert_eq!(
+                quartet_distance(a, b, taxa_count),
+                naive_quartet_distance_of(a, b, taxa_count, &taxa),
+                "{}",
+                newick
+            );
+        }
+    }
+
+    #[test]
+    fn test_quartets_do_not_depend_on_rooting() {
+        let trees = parse_trees("((A,B),(C,(D,E)));(A,B,(C,(D,E)));((A,B),((C,D),E));");
+
+        assert_eq!(quartet_distance(&trees[0], &trees[1], 5), 0);
+        // but ABC, ABD and ABE are stars in the second tree seen as rooted
+        assert_eq!(triplet_distance(&trees[0], &trees[1], 5), 3);
+        // ACDE and BCDE are resolved differently
+        assert_eq!(quartet_distance(&trees[0], &trees[2], 5), 2);
+    }
+
+    #[test]
+    fn test_taxa_missing_from_a_tree() {
+        // only A, B, C and D are in both trees
+        let trees = parse_trees("((A,B),(C,(D,E)));((A,C),(B,D));");
+
+        assert_eq!(quartet_distance(&trees[0], &trees[1], 5), 1);
+        assert_eq!(triplet_distance(&trees[0], &trees[1], 5), 4);
+    }
+
+    #[test]
+    fn test_distance_matrix() {
+        let trees = parse_trees(TREES);
+        let matrix = distance_matrix(&trees, 9, TreeDistance::Quartet);
+
+        assert_eq!(matrix[0][0], 0.0);
+        // the star tree has no resolved quartet, and all 126 quartets of a binary
+        // tree are resolved
+        assert_eq!(matrix[0][3], 126.0);
+        assert_eq!(matrix[1][3], 126.0);
+        assert_eq!(
+            matrix[2][4],
+            naive_quartet_distance(&trees[2], &trees[4], 9) as f64
+        );
+    }
+}
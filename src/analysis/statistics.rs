@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use indextree::NodeId;
+
+use crate::types::{clades::Topology, tree::Tree};
+
+impl Tree {
+    /// The sum of all branch lengths. Missing lengths count as 0.
+    pub fn tree_length(&self) -> f64 {
+        self.preorder()
+            .filter_map(|node_id| self.node(node_id).length())
+            .sum()
+    }
+
+    /// The height of the root, i.e. the distance from the root to the furthest leaf.
+    pub fn root_height(&self) -> f64 {
+        self.root().map_or(0.0, |root| self.node_height(root))
+    }
+
+    /// The Colless imbalance, the sum over the internal nodes of the difference
+    /// between the number of leaves below their two children. For polytomies, the
+    /// differences of all pairs of children are summed.
+    pub fn colless(&self) -> usize {
+        let leaf_counts = self.leaf_counts();
+
+        self.preorder()
+            .map(|node_id| {
+                let counts: Vec<usize> = self
+                    .children(node_id)
+                    .map(|child| leaf_counts[&child])
+                    .collect();
+                let mut sum = 0;
+                for (i, a) in counts.iter().enumerate() {
+                    for b in &counts[i + 1..] {
+                        sum += a.abs_diff(*b);
+                    }
+                }
+                sum
+            })
+            .sum()
+    }
+
+    /// The Sackin imbalance, the sum of the number of branches between every leaf
+    /// and the root.
+    pub fn sackin(&self) -> usize {
+        self.leaves()
+            .map(|leaf| leaf.ancestors(&self.tree).count() - 1)
+            .sum()
+    }
+
+    /// The number of cherries, i.e. of nodes whose only children are two leaves.
+    pub fn cherries(&self) -> usize {
+        self.preorder()
+            .filter(|node_id| {
+                let children: Vec<_> = self.children(*node_id).collect();
+                children.len() == 2 && children.iter().all(|child| self.is_leaf(*child))
+            })
+            .count()
+    }
+
+    /// The γ statistic of Pybus and Harvey (2000), which is negative if the
+    /// branching times are closer to the root than under a pure-birth process and
+    /// positive if they are closer to the leaves. It is defined for bifurcating,
+    /// ultrametric trees with at least three leaves and `None` for other trees,
+    /// including ones whose leaves are not at the same height up to the given
+    /// absolute tolerance (see [`Tree::is_ultrametric`]).
+    pub fn gamma(&self, tolerance: f64) -> Option<f64> {
+        let leaf_count = self.leaves().count();
+        if !self.is_ultrametric(tolerance) {
+            return None;
+        }
+        let heights = self.node_heights();
+
+        let mut branching_times = vec![];
+        for node_id in self.preorder().filter(|node_id| !self.is_leaf(*node_id)) {
+            if self.children(node_id).count() != 2 {
+                return None;
+            }
+            branching_times.push(heights[&node_id]);
+        }
+        if leaf_count < 3 {
+            return None;
+        }
+
+        // the intervals during which there are 2, 3, ..., n lineages
+        branching_times.sort_by(|a, b| b.total_cmp(a));
+        branching_times.push(0.0);
+        let intervals: Vec<f64> = branching_times
+            .windows(2)
+            .map(|times| times[0] - times[1])
+            .collect();
+
+        let total: f64 = intervals
+            .iter()
+            .enumerate()
+            .map(|(i, interval)| (i + 2) as f64 * interval)
+            .sum();
+        let mut partial_sums = 0.0;
+        let mut cumulative = 0.0;
+        for (i, interval) in intervals[..leaf_count - 2].iter().enumerate() {
+            cumulative += (i + 2) as f64 * interval;
+            partial_sums += cumulative;
+        }
+
+        let n = (leaf_count - 2) as f64;
+        Some((partial_sums / n - total / 2.0) / (total * (1.0 / (12.0 * n)).sqrt()))
+    }
+
+    fn leaf_counts(&self) -> HashMap<NodeId, usize> {
+        let mut leaf_counts = HashMap::new();
+        for node_id in self.postorder() {
+            let count = match self.is_leaf(node_id) {
+                true => 1,
+                false => self
+                    .children(node_id)
+                    .map(|child| leaf_counts[&child])
+                    .sum(),
+            };
+            leaf_counts.insert(node_id, count);
+        }
+        leaf_counts
+    }
+}
+
+/// Statistics of a sample of trees, e.g. of an MCMC run read one tree at a time
+/// with a [`TreeReader`](crate::TreeReader), as a table with one column per
+/// statistic and one row per tree.
+///
+/// Trees are numbered by topology in the order their topologies were first seen,
+/// as in [`TopologyCounts`](crate::TopologyCounts). Topologies are compared as
+/// rooted unless the statistics are for unrooted trees.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TreeStatistics {
+    pub names: Vec<String>,
+    pub tree_lengths: Vec<f64>,
+    pub root_heights: Vec<f64>,
+    pub colless: Vec<usize>,
+    pub sackin: Vec<usize>,
+    pub gamma: Vec<Option<f64>>,
+    pub cherries: Vec<usize>,
+    pub topologies: Vec<usize>,
+    unrooted: bool,
+    ultrametric_tolerance: f64,
+    topology_ids: HashMap<Topology, usize>,
+}
+
+impl TreeStatistics {
+    /// The tolerance of the γ statistic for the heights of the leaves defaults to
+    /// 1e-6.
+    pub fn new() -> Self {
+        Self {
+            names: vec![],
+            tree_lengths: vec![],
+            root_heights: vec![],
+            colless: vec![],
+            sackin: vec![],
+            gamma: vec![],
+            cherries: vec![],
+            topologies: vec![],
+            unrooted: false,
+            ultrametric_tolerance: 1e-6,
+            topology_ids: HashMap::new(),
+        }
+    }
+
+    pub fn with_unrooted(mut self, unrooted: bool) -> Self {
+        self.unrooted = unrooted;
+        self
+    }
+
+    /// The absolute tolerance for the heights of the leaves, above which trees are
+    /// not ultrametric and have no γ statistic (see [`Tree::gamma`]).
+    pub fn with_ultrametric_tolerance(mut self, tolerance: f64) -> Self {
+        self.ultrametric_tolerance = tolerance;
+        self
+    }
+
+    pub fn add(&mut self, tree: &Tree) {
+        let taxa_count = tree
+            .leaves()
+            .filter_map(|leaf| tree.node(leaf).taxon())
+            .max()
+            .map_or(0, |taxon| taxon + 1);
+        let topology = tree.topology(taxa_count, self.unrooted);
+        let next_id = self.topology_ids.len();
+        let topology = *self.topology_ids.entry(topology).or_insert(next_id);
+
+        self.names.push(tree.name.clone());
+        self.tree_lengths.push(tree.tree_length());
+        self.root_heights.push(tree.root_height());
+        self.colless.push(tree.colless());
+        self.sackin.push(tree.sackin());
+        self.gamma.push(tree.gamma(self.ultrametric_tolerance));
+        self.cherries.push(tree.cherries());
+        self.topologies.push(topology);
+    }
+
+    /// The number of trees added.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn distinct_topologies(&self) -> usize {
+        self.topology_ids.len()
+    }
+
+    /// Writes the table as tab-separated values with a header, as read by Tracer.
+    /// Undefined γ statistics are written as `NA`.
+    pub fn to_tsv(&self) -> String {
+        let mut tsv = String::from(
+            "tree\ttree_length\troot_height\tcolless\tsackin\tgamma\tcherries\ttopology\n",
+        );
+
+        for i in 0..self.len() {
+            let gamma = self.gamma[i].map_or(String::from("NA"), |gamma| gamma.to_string());
+            tsv.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                self.names[i],
+                self.tree_lengths[i],
+                self.root_heights[i],
+                self.colless[i],
+                self.sackin[i],
+                gamma,
+                self.cherries[i],
+                self.topologies[i]
+            ));
+        }
+
+        tsv
+    }
+}
+
+impl Default for TreeStatistics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'t> Extend<&'t Tree> for TreeStatistics {
+    fn extend<I: IntoIterator<Item = &'t Tree>>(&mut self, trees: I) {
+        for tree in trees {
+            self.add(tree);
+        }
+    }
+}
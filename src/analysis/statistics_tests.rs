@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        analysis::statistics::TreeStatistics,
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::{parser::Parser, tree_reader::TreeReader},
+        types::{nexus::NexusBlock, tree::Tree},
+    };
+
+    fn parse_tree(newick: &str) -> Tree {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        match nexus.blocks.into_iter().next() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees.into_iter().next().unwrap(),
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    #[test]
+    fn test_tree_statistics() {
+        let balanced = parse_tree("((A:1,B:1):2,(C:2,D:2):1);");
+        assert_eq!(balanced.tree_length(), 9.0);
+        assert_eq!(balanced.root_height(), 3.0);
+        assert_eq!(balanced.colless(), 0);
+        assert_eq!(balanced.sackin(), 8);
+        assert_eq!(balanced.cherries(), 2);
+        let gamma = balanced.gamma(0.0).unwrap();
+        assert!((gamma + 24f64.sqrt() / 9.0).abs() < 1e-12);
+
+        let caterpillar = parse_tree("(((A,B),C),D);");
+        assert_eq!(caterpillar.colless(), 3);
+        assert_eq!(caterpillar.sackin(), 9);
+        assert_eq!(caterpillar.cherries(), 1);
+
+        let polytomy = parse_tree("((A,B),C,D,E);");
+        assert_eq!(polytomy.colless(), 3);
+        assert_eq!(polytomy.gamma(0.0), None);
+        assert_eq!(parse_tree("(A:1,B:1);").gamma(0.0), None);
+
+        // D is 0.5 closer to the root than the other leaves
+        let not_ultrametric = parse_tree("((A:1,B:1):2,(C:2,D:1.5):1);");
+        assert_eq!(not_ultrametric.gamma(0.1), None);
+        assert!(not_ultrametric.gamma(0.5).is_some());
+    }
+
+    #[test]
+    fn test_statistics_table() {
+        let text = "#NEXUS
+            BEGIN TREES;
+                TREE t1 = ((A:1,B:1):2,(C:2,D:2):1);
+                TREE t2 = ((C:1,D:1):1,(B:1,A:1):1);
+                TREE t3 = (((A:1,B:1):1,C:2):1,D:3);
+            END;";
+
+        let mut statistics = TreeStatistics::new();
+        for tree in TreeReader::new(Cursor::new(text)).unwrap() {
+            statistics.add(&tree.unwrap());
+        }
+
+        assert_eq!(statistics.len(), 3);
+        assert_eq!(statistics.tree_lengths, vec![9.0, 6.0, 9.0]);
+        assert_eq!(statistics.topologies, vec![0, 0, 1]);
+        assert_eq!(statistics.distinct_topologies(), 2);
+        assert_eq!(
+            statistics.to_tsv().lines().nth(2),
+            Some("t2\t6\t2\t0\t8\t-0.8164965809277261\t2\t0")
+        );
+
+        // as unrooted trees, the first two have the same topology
+        let lexer = Lexer::new("((A,B),(C,D));\n(((A,B),C),D);\n((A,C),(B,D));");
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.first() else {
+            panic!("Expected a trees block.");
+        };
+        let mut unrooted = TreeStatistics::new().with_unrooted(true);
+        unrooted.extend(trees);
+        assert_eq!(unrooted.topologies, vec![0, 0, 1]);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    analysis::clade_counts::CladeCounts,
+    types::tree::{AttributeValue, Tree, TreeNode},
+};
+
+/// How the support of a clade is written into an annotated tree.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SupportFormat {
+    /// As the `frequency` attribute, e.g. `[&frequency=0.95]`.
+    Attribute,
+    /// As the label of the node, rounded to two decimals. Existing labels are
+    /// replaced.
+    Label,
+}
+
+/// Annotates a reference tree with the support of its clades in a sample of trees,
+/// see [`CladeCounts::annotate_support`]. Clades are compared as rooted if the
+/// reference tree is rooted and as unrooted splits otherwise.
+///
+/// Taxa are compared by id, so the reference tree and the sample should come from
+/// files with the same TAXA block.
+pub fn clade_support<'t>(
+    tree: &Tree,
+    trees: impl IntoIterator<Item = &'t Tree>,
+    taxa_count: usize,
+    format: SupportFormat,
+) -> Tree {
+    let mut clade_counts = CladeCounts::new(taxa_count).with_unrooted(!tree.rooted);
+    clade_counts.extend(trees);
+    clade_counts.annotate_support(tree, format)
+}
+
+impl CladeCounts {
+    /// Annotates every internal node of a reference tree, e.g. a maximum likelihood
+    /// tree, but the root with the frequency of its clade in the counted trees. In
+    /// unrooted mode the frequency of the split above the node is used, so both
+    /// children of a bifurcating root get the same support.
+    pub fn annotate_support(&self, tree: &Tree, format: SupportFormat) -> Tree {
+        let mut annotated = tree.clone();
+
+        let node_clades = match self.is_unrooted() {
+            true => tree.node_splits(self.taxa_count()),
+            false => {
+                let mut node_clades = tree.node_clades(self.taxa_count());
+                node_clades.pop();
+                node_clades
+            }
+        };
+
+        for (node_id, clade) in node_clades {
+            if tree.is_leaf(node_id) {
+                continue;
+            }
+
+            let frequency = self.frequency(&clade);
+            let node = annotated.tree[node_id].get_mut();
+            match format {
+                SupportFormat::Attribute => {
+                    node.attributes_mut()
+                        .insert("frequency".to_string(), AttributeValue::Number(frequency));
+                }
+                SupportFormat::Label => {
+                    if let TreeNode::InternalNode { label, .. } = node {
+                        *label = Some(format!("{:.2}", frequency));
+                    }
+                }
+            }
+        }
+
+        annotated
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        analysis::{
+            clade_counts::CladeCounts,
+            support::{SupportFormat, clade_support},
+        },
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::{parser::Parser, tree_reader::TreeReader},
+        types::{
+            nexus::{Nexus, NexusBlock},
+            tree::Tree,
+        },
+        writer::newick::write_newick,
+    };
+
+    const SAMPLE: &str = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=5;
+            TAXLABELS A B C D E;
+        END;
+        BEGIN TREES;
+            TREE s1 = ((A,B),(C,(D,E)));
+            TREE s2 = (A,(B,(C,(D,E))));
+            TREE s3 = ((A,C),(B,(D,E)));
+            TREE s4 = ((A,E),(B,(C,D)));
+        END;";
+
+    fn parse(text: &str) -> Nexus {
+        let lexer = Lexer::new(text);
+        Parser::new(Tokens::new(&lexer)).parse().unwrap()
+    }
+
+    fn trees(nexus: &Nexus) -> Vec<Tree> {
+        match nexus.blocks.get(1) {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees.clone(),
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    fn reference(newick: &str) -> (Nexus, Tree) {
+        let text = format!(
+            "#NEXUS
+            BEGIN TAXA;
+                DIMENSIONS NTAX=5;
+                TAXLABELS A B C D E;
+            END;
+            BEGIN TREES;
+                TREE ml = {};
+            END;",
+            newick
+        );
+        let nexus = parse(&text);
+        let tree = trees(&nexus).remove(0);
+        (nexus, tree)
+    }
+
+    #[test]
+    fn test_unrooted_support() {
+        let sample = trees(&parse(SAMPLE));
+        let (nexus, ml) = reference("((A,B),C,(D,E))");
+
+        let annotated = clade_support(&ml, &sample, 5, SupportFormat::Label);
+        assert_eq!(
+            write_newick(&annotated, &nexus.taxa),
+            "((A,B)0.50,C,(D,E)0.75);"
+        );
+
+        let annotated = clade_support(&ml, &sample, 5, SupportFormat::Attribute);
+        assert_eq!(
+            write_newick(&annotated, &nexus.taxa),
+            "((A,B)[&frequency=0.5],C,(D,E)[&frequency=0.75]);"
+        );
+    }
+
+    #[test]
+    fn test_rooted_support() {
+        let sample = trees(&parse(&SAMPLE.replace("= (", "= [&R] (")));
+        let (nexus, ml) = reference("[&R] ((A,B),(C,(D,E)))");
+
+        let annotated = clade_support(&ml, &sample, 5, SupportFormat::Attribute);
+        assert_eq!(
+            write_newick(&annotated, &nexus.taxa),
+            "[&R] ((A,B)[&frequency=0.25],(C,(D,E)[&frequency=0.75])[&frequency=0.5]);"
+        );
+    }
+
+    #[test]
+    fn test_support_from_streamed_trees() {
+        let sample = trees(&parse(SAMPLE));
+        let (_, ml) = reference("((A,B),C,(D,E))");
+
+        let mut clade_counts = CladeCounts::new(5).with_unrooted(true);
+        for tree in TreeReader::new(Cursor::new(SAMPLE)).unwrap() {
+            clade_counts.add(&tree.unwrap());
+        }
+
+        assert_eq!(
+            clade_counts.annotate_support(&ml, SupportFormat::Attribute),
+            clade_support(&ml, &sample, 5, SupportFormat::Attribute)
+        );
+    }
+}
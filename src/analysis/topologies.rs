@@ -0,0 +1,144 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::types::{clades::Topology, tree::Tree};
+
+/// How often a topology was seen, with the first tree that had it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TopologyStats {
+    pub count: usize,
+    /// The tree representing the topology, with the branch lengths, labels and
+    /// attributes it had in the sample.
+    pub tree: Tree,
+}
+
+/// Counts the distinct topologies of a sample of trees, e.g. of a posterior read
+/// one tree at a time with a [`TreeReader`](crate::TreeReader), to get their
+/// posterior frequencies and credible sets.
+///
+/// By default trees are taken as rooted. Unrooted trees are compared by their
+/// splits instead, so that differently rooted trees have the same topology.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TopologyCounts {
+    taxa_count: usize,
+    unrooted: bool,
+    tree_count: usize,
+    /// The topologies in the order they were first seen.
+    topologies: Vec<(Topology, TopologyStats)>,
+    indices: HashMap<Topology, usize>,
+}
+
+impl TopologyCounts {
+    /// `taxa_count` is the number of taxa of the file the trees belong to.
+    pub fn new(taxa_count: usize) -> Self {
+        Self {
+            taxa_count,
+            unrooted: false,
+            tree_count: 0,
+            topologies: vec![],
+            indices: HashMap::new(),
+        }
+    }
+
+    pub fn with_unrooted(mut self, unrooted: bool) -> Self {
+        self.unrooted = unrooted;
+        self
+    }
+
+    /// Adds a tree and returns the index of its topology, i.e. the number of
+    /// distinct topologies seen before it was first seen.
+    pub fn add(&mut self, tree: &Tree) -> usize {
+        self.tree_count += 1;
+
+        let topology = tree.topology(self.taxa_count, self.unrooted);
+        match self.indices.get(&topology) {
+            Some(index) => {
+                self.topologies[*index].1.count += 1;
+                *index
+            }
+            None => {
+                let index = self.topologies.len();
+                self.indices.insert(topology.clone(), index);
+                self.topologies.push((
+                    topology,
+                    TopologyStats {
+                        count: 1,
+                        tree: tree.clone(),
+                    },
+                ));
+                index
+            }
+        }
+    }
+
+    pub fn taxa_count(&self) -> usize {
+        self.taxa_count
+    }
+
+    pub fn is_unrooted(&self) -> bool {
+        self.unrooted
+    }
+
+    pub fn tree_count(&self) -> usize {
+        self.tree_count
+    }
+
+    /// The number of distinct topologies.
+    pub fn len(&self) -> usize {
+        self.topologies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.topologies.is_empty()
+    }
+
+    pub fn get(&self, topology: &Topology) -> Option<&TopologyStats> {
+        self.indices
+            .get(topology)
+            .map(|index| &self.topologies[*index].1)
+    }
+
+    /// The fraction of trees with the topology.
+    pub fn frequency(&self, topology: &Topology) -> f64 {
+        match (self.get(topology), self.tree_count) {
+            (Some(stats), tree_count) if tree_count > 0 => stats.count as f64 / tree_count as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// The topologies in the order they were first seen.
+    pub fn iter(&self) -> impl Iterator<Item = (&Topology, &TopologyStats)> {
+        self.topologies
+            .iter()
+            .map(|(topology, stats)| (topology, stats))
+    }
+
+    /// The topologies from the most to the least frequent, in the order they were
+    /// first seen if they are equally frequent.
+    pub fn sorted(&self) -> Vec<(&Topology, &TopologyStats)> {
+        let mut sorted: Vec<_> = self.iter().collect();
+        sorted.sort_by_key(|(_, stats)| Reverse(stats.count));
+        sorted
+    }
+
+    /// The credible set of topologies, i.e. the most frequent topologies whose
+    /// frequencies add up to at least `probability`, such as 0.95.
+    pub fn credible_set(&self, probability: f64) -> Vec<(&Topology, &TopologyStats)> {
+        let mut cumulative = 0;
+        self.sorted()
+            .into_iter()
+            .take_while(|(_, stats)| {
+                let included = (cumulative as f64) < probability * self.tree_count as f64;
+                cumulative += stats.count;
+                included
+            })
+            .collect()
+    }
+}
+
+impl<'t> Extend<&'t Tree> for TopologyCounts {
+    fn extend<I: IntoIterator<Item = &'t Tree>>(&mut self, trees: I) {
+        for tree in trees {
+            self.add(tree);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::topologies::TopologyCounts,
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{nexus::NexusBlock, tree::Tree},
+    };
+
+    fn parse_trees(newick: &str) -> Vec<Tree> {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        match nexus.blocks.into_iter().next() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees,
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    #[test]
+    fn test_topology_ignores_lengths_and_child_order() {
+        let trees = parse_trees(
+            "((A:1,B:2):1,(C:1,D:1):3);
+            ((D,C),(B,A)x);
+            (((A,B),C),D);
+            (A,(B,(C,D)));",
+        );
+
+        assert_eq!(trees[0].topology(4, false), trees[1].topology(4, false));
+        assert_ne!(trees[0].topology(4, false), trees[2].topology(4, false));
+        // all of them have the split AB|CD when unrooted
+        for tree in &trees[1..] {
+            assert_eq!(tree.topology(4, true), trees[0].topology(4, true));
+        }
+    }
+
+    #[test]
+    fn test_topology_depends_on_taxa() {
+        let trees = parse_trees("((A,B),C);((A,B),(C,D));");
+
+        assert_ne!(trees[0].topology(4, true), trees[1].topology(4, true));
+    }
+
+    #[test]
+    fn test_topology_frequencies() {
+        let trees = parse_trees(
+            "((A,B),(C,D));
+            ((A,C),(B,D));
+            ((B,A),(D,C));
+            ((A,C),(D,B));
+            ((A,B),(D,C));
+            ((A,D),(B,C));",
+        );
+        let mut counts = TopologyCounts::new(4);
+        counts.extend(&trees);
+
+        assert_eq!(counts.tree_count(), 6);
+        assert_eq!(counts.len(), 3);
+        let sorted = counts.sorted();
+        assert_eq!(
+            sorted
+                .iter()
+                .map(|(_, stats)| stats.count)
+                .collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(sorted[0].1.tree, trees[0]);
+        assert_eq!(sorted[1].1.tree, trees[1]);
+        assert_eq!(counts.frequency(sorted[0].0), 0.5);
+        assert_eq!(counts.frequency(&trees[5].topology(4, false)), 1.0 / 6.0);
+
+        assert_eq!(counts.credible_set(0.5).len(), 1);
+        assert_eq!(counts.credible_set(0.8).len(), 2);
+        assert_eq!(counts.credible_set(0.95).len(), 3);
+    }
+
+    #[test]
+    fn test_unrooted_topology_frequencies() {
+        let trees = parse_trees("((A,B),(C,D));(((A,B),C),D);(A,(B,(C,D)));");
+
+        let mut rooted = TopologyCounts::new(4);
+        rooted.extend(&trees);
+        assert_eq!(rooted.len(), 3);
+
+        let mut unrooted = TopologyCounts::new(4).with_unrooted(true);
+        assert_eq!(unrooted.add(&trees[0]), 0);
+        assert_eq!(unrooted.add(&trees[1]), 0);
+        assert_eq!(unrooted.add(&trees[2]), 0);
+        assert_eq!(unrooted.len(), 1);
+        assert_eq!(unrooted.credible_set(0.95)[0].1.count, 3);
+    }
+}
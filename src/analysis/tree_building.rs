@@ -0,0 +1,355 @@
+use std::fmt::Display;
+
+use indextree::NodeId;
+
+use crate::types::{
+    characters::{CharacterMatrix, DataType},
+    taxa::TaxonId,
+    tree::{Tree, TreeNode},
+};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DistanceMethod {
+    /// Neighbor-Joining (Saitou and Nei 1987), giving an unrooted tree.
+    NeighborJoining,
+    /// BIONJ (Gascuel 1997), Neighbor-Joining with distances to the new nodes
+    /// weighted by their estimated variances, giving an unrooted tree.
+    Bionj,
+    /// UPGMA, giving a rooted ultrametric tree.
+    Upgma,
+}
+
+/// Builds a tree from a symmetric matrix of the distances between all taxa, e.g.
+/// from [`CharacterMatrix::distances`]. Rows and columns are indexed by
+/// [`TaxonId`], so the leaves of the tree refer to the taxa of the file.
+///
+/// Unrooted trees have three children below their root. Neighbor-Joining and
+/// BIONJ keep negative branch lengths if the distances require them.
+///
+/// # Panics
+///
+/// Panics if the matrix is not square.
+pub fn build_tree(distances: &[Vec<f64>], method: DistanceMethod) -> Tree {
+    let taxa_count = distances.len();
+    assert!(
+        distances.iter().all(|row| row.len() == taxa_count),
+        "The distance matrix is not square"
+    );
+
+    let mut tree = Tree {
+        tree: Default::default(),
+        name: match method {
+            DistanceMethod::NeighborJoining => "neighbor_joining",
+            DistanceMethod::Bionj => "bionj",
+            DistanceMethod::Upgma => "upgma",
+        }
+        .to_string(),
+        rooted: method == DistanceMethod::Upgma,
+    };
+    let nodes: Vec<NodeId> = (0..taxa_count)
+        .map(|taxon| tree.tree.new_node(TreeNode::new_leaf(taxon)))
+        .collect();
+
+    match method {
+        DistanceMethod::NeighborJoining => neighbor_joining(&mut tree, distances, nodes, false),
+        DistanceMethod::Bionj => neighbor_joining(&mut tree, distances, nodes, true),
+        DistanceMethod::Upgma => upgma(&mut tree, distances, nodes),
+    }
+
+    tree
+}
+
+fn neighbor_joining(tree: &mut Tree, distances: &[Vec<f64>], mut nodes: Vec<NodeId>, bionj: bool) {
+    let mut distances = distances.to_vec();
+    // BIONJ estimates the variances of the distances as the distances themselves
+    let mut variances = distances.clone();
+
+    while nodes.len() > 3 {
+        let r = nodes.len() as f64;
+        let sums: Vec<f64> = distances.iter().map(|row| row.iter().sum()).collect();
+        let (i, j) = closest_pair(nodes.len(), |i, j| {
+            (r - 2.0) * distances[i][j] - sums[i] - sums[j]
+        });
+
+        let length_i = distances[i][j] / 2.0 + (sums[i] - sums[j]) / (2.0 * (r - 2.0));
+        let length_j = distances[i][j] - length_i;
+
+        let lambda = match bionj && variances[i][j] > 0.0 {
+            true => {
+                let others: f64 = (0..nodes.len())
+                    .filter(|k| *k != i && *k != j)
+                    .map(|k| variances[j][k] - variances[i][k])
+                    .sum();
+                (0.5 + others / (2.0 * (r - 2.0) * variances[i][j])).clamp(0.0, 1.0)
+            }
+            false => 0.5,
+        };
+
+        for k in (0..nodes.len()).filter(|k| *k != i && *k != j) {
+            let distance = lambda * (distances[i][k] - length_i)
+                + (1.0 - lambda) * (distances[j][k] - length_j);
+            let variance = lambda * variances[i][k] + (1.0 - lambda) * variances[j][k]
+                - lambda * (1.0 - lambda) * variances[i][j];
+            (distances[i][k], distances[k][i]) = (distance, distance);
+            (variances[i][k], variances[k][i]) = (variance, variance);
+        }
+
+        nodes[i] = join(
+            tree,
+            TreeNode::new_internal(),
+            [(nodes[i], length_i), (nodes[j], length_j)],
+        );
+        remove(&mut distances, j);
+        remove(&mut variances, j);
+        nodes.remove(j);
+    }
+
+    let root = tree.tree.new_node(TreeNode::new_root());
+    match nodes[..] {
+        [a, b, c] => {
+            let d = &distances;
+            for (node_id, length) in [
+                (a, (d[0][1] + d[0][2] - d[1][2]) / 2.0),
+                (b, (d[0][1] + d[1][2] - d[0][2]) / 2.0),
+                (c, (d[0][2] + d[1][2] - d[0][1]) / 2.0),
+            ] {
+                append(tree, root, node_id, length);
+            }
+        }
+        [a, b] => {
+            append(tree, root, a, distances[0][1] / 2.0);
+            append(tree, root, b, distances[0][1] / 2.0);
+        }
+        _ => {
+            for node_id in nodes {
+                root.append(node_id, &mut tree.tree);
+            }
+        }
+    }
+}
+
+fn upgma(tree: &mut Tree, distances: &[Vec<f64>], mut nodes: Vec<NodeId>) {
+    let mut distances = distances.to_vec();
+    let mut sizes = vec![1.0; nodes.len()];
+    let mut heights = vec![0.0; nodes.len()];
+
+    while nodes.len() > 1 {
+        let (i, j) = closest_pair(nodes.len(), |i, j| distances[i][j]);
+        let height = distances[i][j] / 2.0;
+
+        for k in (0..nodes.len()).filter(|k| *k != i && *k != j) {
+            let distance =
+                (sizes[i] * distances[i][k] + sizes[j] * distances[j][k]) / (sizes[i] + sizes[j]);
+            (distances[i][k], distances[k][i]) = (distance, distance);
+        }
+
+        let node = match nodes.len() {
+            2 => TreeNode::new_root(),
+            _ => TreeNode::new_internal(),
+        };
+        nodes[i] = join(
+            tree,
+            node,
+            [
+                (nodes[i], height - heights[i]),
+                (nodes[j], height - heights[j]),
+            ],
+        );
+        sizes[i] += sizes[j];
+        heights[i] = height;
+
+        remove(&mut distances, j);
+        sizes.remove(j);
+        heights.remove(j);
+        nodes.remove(j);
+    }
+
+    // the last join created the root unless there were fewer than two taxa
+    match nodes[..] {
+        [leaf] if tree.is_leaf(leaf) => {
+            let root = tree.tree.new_node(TreeNode::new_root());
+            root.append(leaf, &mut tree.tree);
+        }
+        [] => {
+            tree.tree.new_node(TreeNode::new_root());
+        }
+        _ => {}
+    }
+}
+
+/// The pair of rows `i < j` with the smallest score, the first one in case of ties.
+fn closest_pair(count: usize, score: impl Fn(usize, usize) -> f64) -> (usize, usize) {
+    let mut closest = (0, 1);
+    let mut best = f64::INFINITY;
+
+    for i in 0..count {
+        for j in i + 1..count {
+            let score = score(i, j);
+            if score < best {
+                best = score;
+                closest = (i, j);
+            }
+        }
+    }
+
+    closest
+}
+
+fn join(tree: &mut Tree, node: TreeNode, children: [(NodeId, f64); 2]) -> NodeId {
+    let node_id = tree.tree.new_node(node);
+    for (child, length) in children {
+        append(tree, node_id, child, length);
+    }
+    node_id
+}
+
+fn append(tree: &mut Tree, parent: NodeId, child: NodeId, new_length: f64) {
+    if let TreeNode::Leaf { length, .. } | TreeNode::InternalNode { length, .. } =
+        tree.tree[child].get_mut()
+    {
+        *length = Some(new_length);
+    }
+    parent.append(child, &mut tree.tree);
+}
+
+/// Removes a row and the corresponding column from a square matrix.
+fn remove(matrix: &mut Vec<Vec<f64>>, index: usize) {
+    matrix.remove(index);
+    for row in matrix {
+        row.remove(index);
+    }
+}
+
+/// Distances between aligned nucleotide sequences, as expected numbers of
+/// substitutions per site except for the p-distance.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SequenceDistance {
+    /// The proportion of differing sites.
+    P,
+    /// Jukes and Cantor (1969), with equal base frequencies and substitution rates.
+    Jc69,
+    /// Kimura (1980), with different rates for transitions and transversions.
+    K80,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SequenceDistanceError {
+    NotNucleotides(DataType),
+    MissingSequence(TaxonId),
+    /// Two taxa have no site where both have an unambiguous nucleotide.
+    NoSharedSites(TaxonId, TaxonId),
+    /// Two taxa differ too much for the distance to be finite.
+    Saturated(TaxonId, TaxonId),
+}
+
+impl Display for SequenceDistanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SequenceDistanceError::NotNucleotides(datatype) => {
+                write!(f, "Expected nucleotide sequences, found {}", datatype)
+            }
+            SequenceDistanceError::MissingSequence(taxon) => {
+                write!(f, "Taxon {} has no sequence", taxon)
+            }
+            SequenceDistanceError::NoSharedSites(a, b) => {
+                write!(f, "Taxa {} and {} have no site in common", a, b)
+            }
+            SequenceDistanceError::Saturated(a, b) => {
+                write!(f, "The distance between taxa {} and {} is saturated", a, b)
+            }
+        }
+    }
+}
+
+impl CharacterMatrix {
+    /// The matrix of the distances between the sequences of all taxa, indexed by
+    /// [`TaxonId`] as expected by [`build_tree`]. `taxa_count` is the number of
+    /// taxa of the file, which all need a sequence.
+    ///
+    /// Sites where either sequence has a gap, missing data or an ambiguous state
+    /// are left out of the comparison of that pair.
+    pub fn distances(
+        &self,
+        taxa_count: usize,
+        distance: SequenceDistance,
+    ) -> Result<Vec<Vec<f64>>, SequenceDistanceError> {
+        if !matches!(
+            self.datatype,
+            DataType::Dna | DataType::Rna | DataType::Nucleotide
+        ) {
+            return Err(SequenceDistanceError::NotNucleotides(self.datatype));
+        }
+
+        let sequences = (0..taxa_count)
+            .map(|taxon| {
+                self.sequence(taxon)
+                    .map(nucleotides)
+                    .ok_or(SequenceDistanceError::MissingSequence(taxon))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut distances = vec![vec![0.0; taxa_count]; taxa_count];
+        for a in 0..taxa_count {
+            for b in a + 1..taxa_count {
+                let (mut sites, mut transitions, mut transversions) = (0, 0, 0);
+                for pair in sequences[a].iter().zip(&sequences[b]) {
+                    if let (Some(x), Some(y)) = pair {
+                        sites += 1;
+                        match (x, y) {
+                            _ if x == y => {}
+                            // A <-> G and C <-> T
+                            _ if x % 2 == y % 2 => transitions += 1,
+                            _ => transversions += 1,
+                        }
+                    }
+                }
+                if sites == 0 {
+                    return Err(SequenceDistanceError::NoSharedSites(a, b));
+                }
+
+                let p = f64::from(transitions) / f64::from(sites);
+                let q = f64::from(transversions) / f64::from(sites);
+                let value = match distance {
+                    SequenceDistance::P => p + q,
+                    SequenceDistance::Jc69 => -0.75 * (1.0 - 4.0 / 3.0 * (p + q)).ln(),
+                    SequenceDistance::K80 => {
+                        -0.5 * (1.0 - 2.0 * p - q).ln() - 0.25 * (1.0 - 2.0 * q).ln()
+                    }
+                };
+                if !value.is_finite() {
+                    return Err(SequenceDistanceError::Saturated(a, b));
+                }
+
+                distances[a][b] = value;
+                distances[b][a] = value;
+            }
+        }
+
+        Ok(distances)
+    }
+}
+
+/// The nucleotides of a sequence as A = 0, C = 1, G = 2 and T/U = 3, with `None`
+/// for anything else, including polymorphisms such as `{AG}`.
+fn nucleotides(sequence: &str) -> Vec<Option<u8>> {
+    let mut states = vec![];
+    let mut in_group = false;
+
+    for state in sequence.chars() {
+        match state.to_ascii_uppercase() {
+            '{' | '(' => in_group = true,
+            '}' | ')' => {
+                in_group = false;
+                states.push(None);
+            }
+            _ if in_group => {}
+            'A' => states.push(Some(0)),
+            'C' => states.push(Some(1)),
+            'G' => states.push(Some(2)),
+            'T' | 'U' => states.push(Some(3)),
+            _ => states.push(None),
+        }
+    }
+
+    states
+}
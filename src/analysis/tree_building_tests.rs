@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::tree_building::{
+            DistanceMethod, SequenceDistance, SequenceDistanceError, build_tree,
+        },
+        types::{
+            characters::{CharacterMatrix, DataType},
+            taxa::Taxa,
+        },
+        writer::newick::write_newick,
+    };
+
+    fn taxa() -> Taxa {
+        ["a", "b", "c", "d", "e"].into_iter().collect()
+    }
+
+    // the example of the Wikipedia article, which is additive
+    fn additive_distances() -> Vec<Vec<f64>> {
+        vec![
+            vec![0.0, 5.0, 9.0, 9.0, 8.0],
+            vec![5.0, 0.0, 10.0, 10.0, 9.0],
+            vec![9.0, 10.0, 0.0, 8.0, 7.0],
+            vec![9.0, 10.0, 8.0, 0.0, 3.0],
+            vec![8.0, 9.0, 7.0, 3.0, 0.0],
+        ]
+    }
+
+    #[test]
+    fn test_neighbor_joining() {
+        let tree = build_tree(&additive_distances(), DistanceMethod::NeighborJoining);
+
+        assert!(!tree.rooted);
+        assert_eq!(
+            write_newick(&tree, &taxa()),
+            "(((a:2,b:3):3,c:4):2,d:2,e:1);"
+        );
+    }
+
+    #[test]
+    fn test_bionj_recovers_additive_distances() {
+        let tree = build_tree(&additive_distances(), DistanceMethod::Bionj);
+
+        assert_eq!(tree.name, "bionj");
+        assert_eq!(
+            write_newick(&tree, &taxa()),
+            "(((a:2,b:3):3,c:4):2,d:2,e:1);"
+        );
+    }
+
+    #[test]
+    fn test_bionj_weights_by_variance() {
+        // not additive, so the new distances depend on the weights
+        let distances = vec![
+            vec![0.0, 3.0, 8.0, 9.0, 10.0],
+            vec![3.0, 0.0, 7.0, 9.0, 9.0],
+            vec![8.0, 7.0, 0.0, 6.0, 7.0],
+            vec![9.0, 9.0, 6.0, 0.0, 4.0],
+            vec![10.0, 9.0, 7.0, 4.0, 0.0],
+        ];
+        let nj = build_tree(&distances, DistanceMethod::NeighborJoining);
+        let bionj = build_tree(&distances, DistanceMethod::Bionj);
+
+        assert_eq!(nj.topology(5, true), bionj.topology(5, true));
+        assert_ne!(nj.tree_length(), bionj.tree_length());
+    }
+
+    #[test]
+    fn test_upgma() {
+        let distances = vec![
+            vec![0.0, 17.0, 21.0, 31.0, 23.0],
+            vec![17.0, 0.0, 30.0, 34.0, 21.0],
+            vec![21.0, 30.0, 0.0, 28.0, 39.0],
+            vec![31.0, 34.0, 28.0, 0.0, 43.0],
+            vec![23.0, 21.0, 39.0, 43.0, 0.0],
+        ];
+        let tree = build_tree(&distances, DistanceMethod::Upgma);
+
+        assert!(tree.is_ultrametric(1e-12));
+        assert_eq!(
+            write_newick(&tree, &taxa()),
+            "[&R] (((a:8.5,b:8.5):2.5,e:11):5.5,(c:14,d:14):2.5);"
+        );
+    }
+
+    #[test]
+    fn test_small_matrices() {
+        for method in [DistanceMethod::NeighborJoining, DistanceMethod::Upgma] {
+            let tree = build_tree(&[vec![0.0, 2.0], vec![2.0, 0.0]], method);
+            assert_eq!(
+                write_newick(&tree, &taxa()).trim_start_matches("[&R] "),
+                "(a:1,b:1);"
+            );
+
+            let tree = build_tree(&[vec![0.0]], method);
+            assert_eq!(tree.leaves().count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_sequence_distances() {
+        let mut matrix = CharacterMatrix::new(DataType::Dna);
+        matrix.sequences = vec![
+            (0, "ACGTACGTAC".to_string()),
+            (1, "GCGTACGTAC".to_string()),
+            // the gap and the polymorphism are left out of the comparisons
+            (2, "CCGTACGT-{AG}".to_string()),
+        ];
+
+        let p = matrix.distances(3, SequenceDistance::P).unwrap();
+        assert_eq!(p[0][1], 0.1);
+        assert_eq!(p[1][0], 0.1);
+        assert_eq!(p[0][2], 1.0 / 8.0);
+        assert_eq!(p[2][2], 0.0);
+
+        let jc69 = matrix.distances(3, SequenceDistance::Jc69).unwrap();
+        assert_eq!(jc69[0][1], -0.75 * (1.0 - 0.4f64 / 3.0).ln());
+
+        // one transition between a and b, one transversion between a and c
+        let k80 = matrix.distances(3, SequenceDistance::K80).unwrap();
+        assert_eq!(k80[0][1], -0.5 * 0.8f64.ln() - 0.25 * 1f64.ln());
+        assert_eq!(
+            k80[0][2],
+            -0.5 * (1.0 - 1.0 / 8.0f64).ln() - 0.25 * (1.0 - 2.0 / 8.0f64).ln()
+        );
+    }
+
+    #[test]
+    fn test_sequence_distance_errors() {
+        let mut matrix = CharacterMatrix::new(DataType::Dna);
+        matrix.sequences = vec![(0, "ACGT".to_string()), (1, "CATG".to_string())];
+
+        assert_eq!(
+            matrix.distances(3, SequenceDistance::P),
+            Err(SequenceDistanceError::MissingSequence(2))
+        );
+        assert!(matrix.distances(2, SequenceDistance::P).is_ok());
+        assert_eq!(
+            matrix.distances(2, SequenceDistance::Jc69),
+            Err(SequenceDistanceError::Saturated(0, 1))
+        );
+
+        matrix.sequences[1].1 = "????".to_string();
+        assert_eq!(
+            matrix.distances(2, SequenceDistance::P),
+            Err(SequenceDistanceError::NoSharedSites(0, 1))
+        );
+
+        matrix.datatype = DataType::Protein;
+        assert_eq!(
+            matrix.distances(2, SequenceDistance::P),
+            Err(SequenceDistanceError::NotNucleotides(DataType::Protein))
+        );
+    }
+}
@@ -0,0 +1,456 @@
+use std::ops::Range;
+
+use crate::analysis::quartets::{TreeNodes, pairs, shared_leaves};
+
+/// The color of a leaf of the second tree while counting the triplets of a node of
+/// the first one: the leaves of the first tree paired with other leaves below the
+/// node, the ones outside the node, or the others.
+#[derive(PartialEq, Clone, Copy)]
+enum Color {
+    None,
+    Ingroup,
+    Outgroup,
+}
+
+impl Color {
+    fn counts(self) -> (i64, i64) {
+        match self {
+            Color::None => (0, 0),
+            Color::Ingroup => (1, 0),
+            Color::Outgroup => (0, 1),
+        }
+    }
+}
+
+/// Sums over a range of nodes of the second tree of the number of ingroup (s) and
+/// outgroup (b) leaves below them, of these numbers for their heavy child (hs and
+/// hb) and of their products, as well as of the products s·b of their light
+/// children.
+#[derive(Clone, Copy, Default)]
+struct Sums {
+    s: i64,
+    b: i64,
+    sb: i64,
+    hs: i64,
+    hb: i64,
+    hs_b: i64,
+    hb_s: i64,
+    light: i64,
+}
+
+impl Sums {
+    fn add(&mut self, add: Add, len: i64) {
+        self.sb += add.s * self.b + add.b * self.s + add.s * add.b * len;
+        self.hs_b += add.hs * self.b + add.b * self.hs + add.hs * add.b * len;
+        self.hb_s += add.hb * self.s + add.s * self.hb + add.hb * add.s * len;
+        self.s += add.s * len;
+        self.b += add.b * len;
+        self.hs += add.hs * len;
+        self.hb += add.hb * len;
+    }
+
+    fn merge(self, other: Sums) -> Sums {
+        Sums {
+            s: self.s + other.s,
+            b: self.b + other.b,
+            sb: self.sb + other.sb,
+            hs: self.hs + other.hs,
+            hb: self.hb + other.hb,
+            hs_b: self.hs_b + other.hs_b,
+            hb_s: self.hb_s + other.hb_s,
+            light: self.light + other.light,
+        }
+    }
+}
+
+/// A change of the counts of every node of a range, see [`Sums`].
+#[derive(PartialEq, Clone, Copy, Default)]
+struct Add {
+    s: i64,
+    b: i64,
+    hs: i64,
+    hb: i64,
+}
+
+impl Add {
+    fn merge(self, other: Add) -> Add {
+        Add {
+            s: self.s + other.s,
+            b: self.b + other.b,
+            hs: self.hs + other.hs,
+            hb: self.hb + other.hb,
+        }
+    }
+}
+
+/// A segment tree over the nodes of the second tree in heavy path order, with
+/// lazy additions to ranges.
+struct SegmentTree {
+    sums: Vec<Sums>,
+    pending: Vec<Add>,
+    len: usize,
+}
+
+impl SegmentTree {
+    fn new(values: &[Sums]) -> Self {
+        let mut tree = Self {
+            sums: vec![Sums::default(); 4 * values.len().max(1)],
+            pending: vec![Add::default(); 4 * values.len().max(1)],
+            len: values.len(),
+        };
+        tree.build(1, 0..values.len(), values);
+        tree
+    }
+
+    fn build(&mut self, index: usize, range: Range<usize>, values: &[Sums]) {
+        if range.len() == 1 {
+            self.sums[index] = values[range.start];
+            return;
+        }
+        let middle = (range.start + range.end) / 2;
+        self.build(2 * index, range.start..middle, values);
+        self.build(2 * index + 1, middle..range.end, values);
+        self.sums[index] = self.sums[2 * index].merge(self.sums[2 * index + 1]);
+    }
+
+    fn push(&mut self, index: usize, range: &Range<usize>) {
+        let add = std::mem::take(&mut self.pending[index]);
+        if add == Add::default() {
+            return;
+        }
+        let middle = (range.start + range.end) / 2;
+        for (child, len) in [
+            (2 * index, middle - range.start),
+            (2 * index + 1, range.end - middle),
+        ] {
+            self.sums[child].add(add, len as i64);
+            self.pending[child] = self.pending[child].merge(add);
+        }
+    }
+
+    fn add(&mut self, range: Range<usize>, add: Add) {
+        if !range.is_empty() {
+            self.add_to(1, 0..self.len, &range, add);
+        }
+    }
+
+    fn add_to(&mut self, index: usize, node_range: Range<usize>, range: &Range<usize>, add: Add) {
+        if range.end <= node_range.start || node_range.end <= range.start {
+            return;
+        }
+        if range.start <= node_range.start && node_range.end <= range.end {
+            self.sums[index].add(add, node_range.len() as i64);
+            self.pending[index] = self.pending[index].merge(add);
+            return;
+        }
+        self.push(index, &node_range);
+        let middle = (node_range.start + node_range.end) / 2;
+        self.add_to(2 * index, node_range.start..middle, range, add);
+        self.add_to(2 * index + 1, middle..node_range.end, range, add);
+        self.sums[index] = self.sums[2 * index].merge(self.sums[2 * index + 1]);
+    }
+
+    /// Adds to the products of the light children of a node, which no range
+    /// addition changes.
+    fn add_light(&mut self, position: usize, light: i64) {
+        let (mut index, mut range) = (1, 0..self.len);
+        loop {
+            self.sums[index].light += light;
+            if range.len() == 1 {
+                return;
+            }
+            let middle = (range.start + range.end) / 2;
+            (index, range) = match position < middle {
+                true => (2 * index, range.start..middle),
+                false => (2 * index + 1, middle..range.end),
+            };
+        }
+    }
+
+    fn sum(&mut self, range: Range<usize>) -> Sums {
+        match range.is_empty() {
+            true => Sums::default(),
+            false => self.sum_of(1, 0..self.len, &range),
+        }
+    }
+
+    fn sum_of(&mut self, index: usize, node_range: Range<usize>, range: &Range<usize>) -> Sums {
+        if range.end <= node_range.start || node_range.end <= range.start {
+            return Sums::default();
+        }
+        if range.start <= node_range.start && node_range.end <= range.end {
+            return self.sums[index];
+        }
+        self.push(index, &node_range);
+        let middle = (node_range.start + node_range.end) / 2;
+        self.sum_of(2 * index, node_range.start..middle, range)
+            .merge(self.sum_of(2 * index + 1, middle..node_range.end, range))
+    }
+}
+
+/// The second tree with colored leaves. Its nodes are split into heavy paths, so
+/// that the path from a leaf to the root crosses O(log n) of them, and the sums
+/// of every path are kept in a [`SegmentTree`]. Coloring a leaf and counting the
+/// triplets of a leaf both take O(log² n) time.
+struct ColoredTree<'t> {
+    nodes: &'t TreeNodes,
+    heads: Vec<usize>,
+    positions: Vec<usize>,
+    sums: SegmentTree,
+    colors: Vec<Color>,
+    ingroup: i64,
+    outgroup: i64,
+}
+
+impl<'t> ColoredTree<'t> {
+    /// All leaves in both trees start as outgroup leaves.
+    fn new(nodes: &'t TreeNodes, shared: &[i64]) -> Self {
+        let mut sizes = vec![1; nodes.len()];
+        for node in 0..nodes.len() {
+            for child in &nodes.children[node] {
+                sizes[node] += sizes[*child];
+            }
+        }
+        let heavy: Vec<Option<usize>> = nodes
+            .children
+            .iter()
+            .map(|children| children.iter().copied().max_by_key(|child| sizes[*child]))
+            .collect();
+
+        // every heavy path is numbered from its top, before the light subtrees
+        let mut heads = vec![0; nodes.len()];
+        let mut positions = vec![0; nodes.len()];
+        let mut stack = vec![(nodes.root(), nodes.root())];
+        let mut position = 0;
+        while let Some((node, head)) = stack.pop() {
+            heads[node] = head;
+            positions[node] = position;
+            position += 1;
+            for child in &nodes.children[node] {
+                if Some(*child) != heavy[node] {
+                    stack.push((*child, *child));
+                }
+            }
+            if let Some(child) = heavy[node] {
+                stack.push((child, head));
+            }
+        }
+
+        let mut values = vec![Sums::default(); nodes.len()];
+        for node in 0..nodes.len() {
+            let hb = heavy[node].map_or(0, |child| shared[child]);
+            values[positions[node]] = Sums {
+                b: shared[node],
+                hb,
+                ..Sums::default()
+            };
+        }
+
+        Self {
+            nodes,
+            heads,
+            positions,
+            sums: SegmentTree::new(&values),
+            colors: (0..nodes.len())
+                .map(
+                    |node| match nodes.taxa[node].is_some() && shared[node] == 1 {
+                        true => Color::Outgroup,
+                        false => Color::None,
+                    },
+                )
+                .collect(),
+            ingroup: 0,
+            outgroup: shared[nodes.root()],
+        }
+    }
+
+    fn node(&mut self, node: usize) -> Sums {
+        let position = self.positions[node];
+        self.sums.sum(position..position + 1)
+    }
+
+    fn set_color(&mut self, leaf: usize, color: Color) {
+        let (old_s, old_b) = self.colors[leaf].counts();
+        let (new_s, new_b) = color.counts();
+        let (s, b) = (new_s - old_s, new_b - old_b);
+        if (s, b) == (0, 0) {
+            return;
+        }
+        self.colors[leaf] = color;
+        self.ingroup += s;
+        self.outgroup += b;
+
+        let mut node = leaf;
+        loop {
+            let head = self.heads[node];
+            let parent = self.nodes.parents[head];
+            if let Some(parent) = parent {
+                let counts = self.node(head);
+                let light = s * counts.b + b * counts.s + s * b;
+                self.sums.add_light(self.positions[parent], light);
+            }
+
+            let (top, bottom) = (self.positions[head], self.positions[node]);
+            self.sums.add(top..bottom + 1, Add { s, b, hs: 0, hb: 0 });
+            // above the node, the path continues through heavy children
+            self.sums.add(
+                top..bottom,
+                Add {
+                    s: 0,
+                    b: 0,
+                    hs: s,
+                    hb: b,
+                },
+            );
+
+            match parent {
+                Some(parent) => node = parent,
+                None => return,
+            }
+        }
+    }
+
+    /// The numbers of pairs of an ingroup leaf y and an outgroup leaf z which form
+    /// the triplets xy|z, xz|y and yz|x with the given leaf x.
+    fn triplets(&mut self, leaf: usize) -> (i64, i64, i64) {
+        let (ingroup, outgroup) = (self.ingroup, self.outgroup);
+        let (mut xy, mut xz, mut yz) = (0, 0, 0);
+
+        // at every ancestor, y is below it but not on the path to x, and z is not
+        // below it, or the other way around for xz|y; for yz|x, both are below
+        // another child
+        let mut node = leaf;
+        loop {
+            let head = self.heads[node];
+            let path = self.sums.sum(self.positions[head]..self.positions[node]);
+            xy += outgroup * path.s - path.sb - outgroup * path.hs + path.hs_b;
+            xz += ingroup * path.b - path.sb - ingroup * path.hb + path.hb_s;
+            yz += path.light;
+
+            let Some(parent) = self.nodes.parents[head] else {
+                return (xy, xz, yz);
+            };
+            let (above, child) = (self.node(parent), self.node(head));
+            xy += (above.s - child.s) * (outgroup - above.b);
+            xz += (above.b - child.b) * (ingroup - above.s);
+            yz += above.light + above.hs * above.hb - child.s * child.b;
+            node = parent;
+        }
+    }
+}
+
+/// Counts the triplets resolved in the first tree which are resolved the same way
+/// and differently in the second one. A triplet xy|z is counted at the most recent
+/// common ancestor of x and y, with the smaller half trick: the ingroup leaves are
+/// kept from the child with the most leaves, so that every leaf changes color
+/// O(log n) times.
+struct TripletCounter<'t> {
+    nodes: &'t TreeNodes,
+    /// The leaf of the second tree with the taxon of every leaf of the first one.
+    partners: Vec<Option<usize>>,
+    shared: Vec<i64>,
+    /// The first node of the subtree of every node in postorder.
+    firsts: Vec<usize>,
+    other: ColoredTree<'t>,
+    same: i64,
+    different: i64,
+}
+
+impl TripletCounter<'_> {
+    fn set_colors(&mut self, node: usize, color: Color) {
+        for descendant in self.firsts[node]..=node {
+            if let Some(leaf) = self.partners[descendant] {
+                self.other.set_color(leaf, color);
+            }
+        }
+    }
+
+    fn count(&mut self, node: usize, keep: bool) {
+        let nodes = self.nodes;
+        let heavy = nodes.children[node]
+            .iter()
+            .copied()
+            .max_by_key(|child| self.shared[*child]);
+        let light = || {
+            nodes.children[node]
+                .iter()
+                .copied()
+                .filter(move |child| Some(*child) != heavy)
+        };
+
+        for child in light() {
+            self.count(child, false);
+        }
+        if let Some(heavy) = heavy {
+            self.count(heavy, true);
+        }
+
+        // the leaves of the light children are paired with the ones before them
+        for child in light() {
+            self.set_colors(child, Color::None);
+        }
+        for child in light() {
+            for descendant in self.firsts[child]..=child {
+                if let Some(leaf) = self.partners[descendant] {
+                    let (xy, xz, yz) = self.other.triplets(leaf);
+                    self.same += xy;
+                    self.different += xz + yz;
+                }
+            }
+            self.set_colors(child, Color::Ingroup);
+        }
+        if let Some(leaf) = self.partners[node] {
+            self.other.set_color(leaf, Color::Ingroup);
+        }
+
+        if !keep {
+            self.set_colors(node, Color::Outgroup);
+        }
+    }
+}
+
+/// The number of triplets of taxa with a different topology in the two trees,
+/// seen as rooted: resolved differently, or resolved in one and a star in the
+/// other. See [`TreeDistance::Triplet`](crate::TreeDistance::Triplet).
+pub(crate) fn different_triplets(a: &TreeNodes, b: &TreeNodes) -> usize {
+    if a.len() == 0 || b.len() == 0 {
+        return 0;
+    }
+    let (shared_a, shared_b) = (shared_leaves(a, b), shared_leaves(b, a));
+    let taxa = shared_a[a.root()];
+
+    // a resolved triplet xy|z is seen at the most recent common ancestor of x and y
+    let resolved = |nodes: &TreeNodes, shared: &[i64]| -> i64 {
+        (0..nodes.len())
+            .map(|node| {
+                pairs(nodes.children[node].iter().map(|child| shared[*child]))
+                    * (taxa - shared[node])
+            })
+            .sum()
+    };
+    let resolved_a = resolved(a, &shared_a);
+    let resolved_b = resolved(b, &shared_b);
+
+    let mut firsts: Vec<usize> = (0..a.len()).collect();
+    for node in 0..a.len() {
+        if let Some(child) = a.children[node].first() {
+            firsts[node] = firsts[*child];
+        }
+    }
+    let mut counter = TripletCounter {
+        nodes: a,
+        partners: a
+            .taxa
+            .iter()
+            .map(|taxon| b.leaves.get(&(*taxon)?).copied())
+            .collect(),
+        shared: shared_a,
+        firsts,
+        other: ColoredTree::new(b, &shared_b),
+        same: 0,
+        different: 0,
+    };
+    counter.count(a.root(), true);
+
+    (resolved_a + resolved_b - 2 * counter.same - counter.different) as usize
+}
@@ -0,0 +1,257 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    str,
+};
+
+use memmap2::Mmap;
+
+use crate::{
+    lexer::{lexer::Lexer, tokens::Tokens},
+    parser::parser::{Parser, ParsingError},
+    types::nexus::Nexus,
+};
+
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    InvalidUtf8,
+    Parsing(ParsingError),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Io(error) => write!(f, "Reading the input failed: {}", error),
+            InputError::InvalidUtf8 => write!(f, "The input is not valid UTF-8"),
+            InputError::Parsing(error) => write!(f, "Parsing the input failed: {}", error),
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(error: io::Error) -> Self {
+        InputError::Io(error)
+    }
+}
+
+impl From<ParsingError> for InputError {
+    fn from(error: ParsingError) -> Self {
+        InputError::Parsing(error)
+    }
+}
+
+/// Compression formats of NEXUS inputs, detected from their magic bytes.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if bytes.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Wraps a reader of compressed data into a reader of the decompressed data.
+    #[cfg(feature = "compression")]
+    pub fn decoder<'r>(self, reader: impl BufRead + 'r) -> io::Result<Box<dyn BufRead + 'r>> {
+        Ok(match self {
+            Compression::None => Box::new(reader),
+            Compression::Gzip => {
+                Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+            }
+            Compression::Bzip2 => {
+                Box::new(BufReader::new(bzip2::bufread::MultiBzDecoder::new(reader)))
+            }
+            Compression::Xz => Box::new(BufReader::new(
+                xz2::bufread::XzDecoder::new_multi_decoder(reader),
+            )),
+            Compression::Zstd => Box::new(BufReader::new(
+                zstd::stream::read::Decoder::with_buffer(reader)?,
+            )),
+        })
+    }
+
+    /// Wraps a reader of compressed data into a reader of the decompressed data.
+    #[cfg(not(feature = "compression"))]
+    pub fn decoder<'r>(self, reader: impl BufRead + 'r) -> io::Result<Box<dyn BufRead + 'r>> {
+        match self {
+            Compression::None => Ok(Box::new(reader)),
+            compression => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "{:?} compressed inputs require the compression feature",
+                    compression
+                ),
+            )),
+        }
+    }
+}
+
+/// Opens a file as a reader over its contents, decompressing them on the fly if
+/// the file is compressed.
+pub fn open_decompressed(path: impl AsRef<Path>) -> Result<Box<dyn BufRead>, InputError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let compression = Compression::detect(reader.fill_buf()?);
+    Ok(compression.decoder(reader)?)
+}
+
+/// The contents of a NEXUS file. Uncompressed files are memory-mapped, so their
+/// contents are paged in by the operating system instead of being copied onto the
+/// heap. Compressed files are decompressed into memory as a whole, which takes as
+/// much memory as the uncompressed file; [`TreeReader`](crate::TreeReader) reads
+/// them one tree at a time instead.
+pub enum NexusInput {
+    Mapped(Mmap),
+    Owned(String),
+}
+
+impl NexusInput {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let file = File::open(path)?;
+
+        // SAFETY: as with any reader, the file must not be modified while it is parsed
+        let mmap = unsafe { Mmap::map(&file) }?;
+
+        let compression = Compression::detect(&mmap);
+        if compression == Compression::None {
+            return Ok(NexusInput::Mapped(mmap));
+        }
+
+        let mut content = vec![];
+        compression.decoder(&mmap[..])?.read_to_end(&mut content)?;
+        let content = String::from_utf8(content).map_err(|_| InputError::InvalidUtf8)?;
+
+        Ok(NexusInput::Owned(content))
+    }
+
+    pub fn as_str(&self) -> Result<&str, InputError> {
+        match self {
+            NexusInput::Mapped(mmap) => str::from_utf8(mmap).map_err(|_| InputError::InvalidUtf8),
+            NexusInput::Owned(content) => Ok(content),
+        }
+    }
+
+    pub fn parse(&self) -> Result<Nexus, InputError> {
+        let lexer = Lexer::new(self.as_str()?);
+        let tokens = Tokens::new(&lexer);
+
+        let mut parser = Parser::new(tokens);
+        Ok(parser.parse()?)
+    }
+
+    /// Parses the input as plain Newick, see [`Parser::parse_newick`].
+    pub fn parse_newick(&self) -> Result<Nexus, InputError> {
+        let lexer = Lexer::new(self.as_str()?);
+        let tokens = Tokens::new(&lexer);
+
+        let mut parser = Parser::new(tokens);
+        Ok(parser.parse_newick()?)
+    }
+}
+
+impl From<String> for NexusInput {
+    fn from(content: String) -> Self {
+        NexusInput::Owned(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_memory_mapped_input() {
+        let path = std::env::temp_dir().join("nexus_test_memory_mapped_input.nex");
+        fs::write(
+            &path,
+            "#NEXUS
+            BEGIN taxa;
+                DIMENSIONS ntax=2;
+                TAXLABELS Apes Humans;
+            END;",
+        )
+        .unwrap();
+
+        let input = NexusInput::open(&path).unwrap();
+        let result = input.parse().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(input, NexusInput::Mapped(_)));
+        assert_eq!(result.taxa.labels(), &["Apes", "Humans"]);
+    }
+
+    #[test]
+    fn test_invalid_utf8_input() {
+        let path = std::env::temp_dir().join("nexus_test_invalid_utf8_input.nex");
+        fs::write(&path, b"#NEXUS\xff").unwrap();
+
+        let input = NexusInput::open(&path).unwrap();
+        let result = input.parse();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(InputError::InvalidUtf8)));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_compressed_inputs() {
+        use std::io::Write;
+
+        let content = "#NEXUS
+            BEGIN taxa;
+                DIMENSIONS ntax=2;
+                TAXLABELS Apes Humans;
+            END;";
+
+        let mut gzip = flate2::write::GzEncoder::new(vec![], Default::default());
+        gzip.write_all(content.as_bytes()).unwrap();
+        let mut bzip2 = bzip2::write::BzEncoder::new(vec![], Default::default());
+        bzip2.write_all(content.as_bytes()).unwrap();
+        let mut xz = xz2::write::XzEncoder::new(vec![], 6);
+        xz.write_all(content.as_bytes()).unwrap();
+
+        for (compression, compressed) in [
+            (Compression::Gzip, gzip.finish().unwrap()),
+            (Compression::Bzip2, bzip2.finish().unwrap()),
+            (Compression::Xz, xz.finish().unwrap()),
+            (
+                Compression::Zstd,
+                zstd::encode_all(content.as_bytes(), 0).unwrap(),
+            ),
+        ] {
+            assert_eq!(Compression::detect(&compressed), compression);
+
+            let path = std::env::temp_dir()
+                .join(format!("nexus_test_compressed_input_{:?}.nex", compression));
+            fs::write(&path, compressed).unwrap();
+
+            let input = NexusInput::open(&path).unwrap();
+            let result = input.parse().unwrap();
+            fs::remove_file(&path).unwrap();
+
+            // compressed files cannot be mapped and are decompressed as a whole
+            assert!(matches!(input, NexusInput::Owned(_)));
+            assert_eq!(input.as_str().unwrap(), content);
+            assert_eq!(result.taxa.labels(), &["Apes", "Humans"]);
+        }
+    }
+}
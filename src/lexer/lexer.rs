@@ -1,6 +1,7 @@
+use std::fmt::Display;
+
 use crate::lexer::tokens::Token;
 use logos::Logos;
-use std::ops::Range;
 
 #[derive(Logos, Debug, PartialEq, Clone)]
 #[logos()]
@@ -9,6 +10,8 @@ pub enum LexerToken {
     EOS,
     #[regex(r"\[#(?P<comment>[^\]]*)\]")]
     Comment,
+    #[regex(r"\[&[^\]]*\]")]
+    Annotation,
     #[regex(r"[\x00-\x06\t\n ]+")]
     Whitespace,
     #[regex(r"[()\[\]{}\/\\,:=*'`<>~]")]
@@ -22,67 +25,70 @@ pub enum LexerToken {
     Number,
 }
 
+/// Input which is no token, such as an unterminated quoted word, comment or
+/// annotation.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LexingError {
+    /// The byte offset the invalid input starts at.
+    pub offset: usize,
+}
+
+impl Display for LexingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid token at byte {}", self.offset)
+    }
+}
+
+/// Lazily tokenizes a NEXUS file. Tokens are identified by the byte offset they
+/// start at and are only lexed when requested, so no token storage is kept.
 pub struct Lexer<'a> {
     content: &'a str,
-    tokens: Vec<Token<'a>>,
-    ranges: Vec<Range<usize>>,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(content: &'a str) -> Self {
-        let lexer = LexerToken::lexer(content).spanned();
-        let mut tokens = vec![];
-        let mut ranges = vec![];
+        Self { content }
+    }
 
-        for (result, range) in lexer {
-            let slice = &content[range.clone()];
+    /// Returns the token starting at byte `offset` together with the offset it ends at,
+    /// or `None` at the end of the input.
+    pub fn token_at(&self, offset: usize) -> Option<Result<(Token<'a>, usize), LexingError>> {
+        let content = self.content;
+        let mut lexer = LexerToken::lexer(content.get(offset..)?);
 
-            let token = match result {
-                Ok(lexer_token) => match lexer_token {
-                    LexerToken::EOS => Token::EOS,
-                    LexerToken::Comment => Token::Comment(
-                        &content[range.clone()].trim_matches(|c| c == '[' || c == '#' || c == ']'),
-                    ),
-                    LexerToken::Whitespace => Token::Whitespace(slice),
-                    LexerToken::Punctuation => Token::Punctuation(slice),
-                    LexerToken::Word => Token::Word(slice),
-                    LexerToken::QuotedWord => Token::QuotedWord(&slice[1..slice.len() - 1]),
-                    LexerToken::Number => {
-                        if let Ok(number) = &slice.parse::<i32>() {
-                            Token::Integer(*number)
-                        } else {
-                            Token::Float(slice.parse::<f64>().unwrap())
-                        }
-                    }
-                },
-                Err(_) => {
-                    dbg!(tokens);
-                    panic!("Tokenization failed.")
-                }
-            };
+        let result = lexer.next()?;
+        let range = offset + lexer.span().start..offset + lexer.span().end;
+        let slice = &content[range.clone()];
 
-            tokens.push(token);
-            ranges.push(range);
-        }
+        let Ok(lexer_token) = result else {
+            return Some(Err(LexingError {
+                offset: range.start,
+            }));
+        };
 
-        Self {
-            content,
-            tokens,
-            ranges,
-        }
-    }
+        let token = match lexer_token {
+            LexerToken::EOS => Token::EOS,
+            LexerToken::Comment => {
+                Token::Comment(slice.trim_matches(|c| c == '[' || c == '#' || c == ']'))
+            }
+            LexerToken::Annotation => Token::Annotation(&slice[2..slice.len() - 1]),
+            LexerToken::Whitespace => Token::Whitespace(slice),
+            LexerToken::Punctuation => Token::Punctuation(slice),
+            LexerToken::Word => Token::Word(slice),
+            LexerToken::QuotedWord => Token::QuotedWord(&slice[1..slice.len() - 1]),
+            LexerToken::Number => {
+                if let Ok(number) = &slice.parse::<i32>() {
+                    Token::Integer(*number)
+                } else {
+                    Token::Float(slice.parse::<f64>().unwrap())
+                }
+            }
+        };
 
-    pub fn get(&self, index: usize) -> Option<&Token> {
-        self.tokens.get(index)
-    }
-    pub fn slice_from_to(&self, from_token: usize, to_token: usize) -> &'a str {
-        let start = self.ranges.get(from_token).unwrap().start;
-        let end = self.ranges.get(to_token - 1).unwrap().end;
-        &self.content[start..end]
+        Some(Ok((token, range.end)))
     }
-    pub fn slice(&self, token: usize) -> &'a str {
-        let start = self.ranges.get(token).unwrap().start;
-        let end = self.ranges.get(token).unwrap().end;
-        &self.content[start..end]
+
+    pub fn slice_from_to(&self, from: usize, to: usize) -> &'a str {
+        &self.content[from..to]
     }
 }
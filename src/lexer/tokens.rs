@@ -2,13 +2,15 @@ use std::fmt::Display;
 
 use logos::Logos;
 
-use crate::lexer::lexer::Lexer;
+use crate::lexer::lexer::{Lexer, LexingError};
 
 #[derive(Logos, Debug, PartialEq, Clone)]
 #[logos()]
 pub enum Token<'a> {
     EOS,
     Comment(&'a str),
+    /// A FigTree/BEAST annotation such as `[&rate=1.2]`, without its brackets and `&`.
+    Annotation(&'a str),
     Whitespace(&'a str),
     Punctuation(&'a str),
     Word(&'a str),
@@ -22,6 +24,7 @@ impl Display for Token<'_> {
         match self {
             Token::EOS => write!(f, "EOS"),
             Token::Comment(comment) => write!(f, "Comment: {}", comment),
+            Token::Annotation(annotation) => write!(f, "Annotation: {}", annotation),
             Token::Whitespace(_) => write!(f, "Whitespace"),
             Token::Punctuation(punctuation) => write!(f, "Punctuation: {}", punctuation),
             Token::Word(word) => write!(f, "Word: {}", word),
@@ -32,18 +35,51 @@ impl Display for Token<'_> {
     }
 }
 
+/// A cursor over the tokens of a [`Lexer`]. The cursor is the byte offset of the
+/// next token, and a peeked token is kept until it is consumed, so that peeking
+/// before consuming only lexes it once. The tokens end at the first input which
+/// cannot be lexed, see [`Tokens::error`].
+#[derive(Clone)]
 pub struct Tokens<'a> {
     lexer: &'a Lexer<'a>,
     cursor: usize,
+    last_token_start: usize,
+    /// The token at the cursor with the offset it ends at, if it was peeked.
+    peeked: Option<(Token<'a>, usize)>,
+    error: Option<LexingError>,
 }
 
 impl<'a> Tokens<'a> {
     pub fn new(lexer: &'a Lexer<'a>) -> Self {
-        Tokens { lexer, cursor: 0 }
+        Tokens {
+            lexer,
+            cursor: 0,
+            last_token_start: 0,
+            peeked: None,
+            error: None,
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&Token<'a>> {
+        if self.peeked.is_none() {
+            self.peeked = self.lex();
+        }
+        self.peeked.as_ref().map(|(token, _)| token)
+    }
+
+    fn lex(&mut self) -> Option<(Token<'a>, usize)> {
+        match self.lexer.token_at(self.cursor)? {
+            Ok(token) => Some(token),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
     }
 
-    pub fn peek(&mut self) -> Option<&Token> {
-        self.lexer.get(self.cursor())
+    /// The input which could not be lexed, once the tokens reached it.
+    pub fn error(&self) -> Option<&LexingError> {
+        self.error.as_ref()
     }
 
     pub fn cursor(&self) -> usize {
@@ -51,6 +87,9 @@ impl<'a> Tokens<'a> {
     }
 
     pub fn set_cursor(&mut self, new_cursor: usize) {
+        if new_cursor != self.cursor {
+            self.peeked = None;
+        }
         self.cursor = new_cursor;
     }
 
@@ -59,20 +98,21 @@ impl<'a> Tokens<'a> {
     }
 
     pub fn last_slice(&self) -> &'a str {
-        self.lexer.slice(self.cursor - 1)
+        self.lexer.slice_from_to(self.last_token_start, self.cursor)
     }
 }
 
-impl<'a> Iterator for Tokens<'a>
-where
-    Token<'a>: Clone,
-{
-    type Item = &'a Token<'a>;
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let old_cursor = self.cursor();
-        self.set_cursor(old_cursor + 1);
-        self.lexer.get(old_cursor)
+        let (token, end) = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.lex()?,
+        };
+        self.last_token_start = self.cursor;
+        self.cursor = end;
+        Some(token)
     }
 }
 
@@ -85,18 +125,28 @@ mod tests {
         let lexer = Lexer::new("#NEXUS;\nBEGIN TREES;   \t  word[#some comment()]other_word;");
         let mut tokens = Tokens::new(&lexer);
 
-        assert_eq!(tokens.next(), Some(&Token::Word("#NEXUS")));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace("\n")));
-        assert_eq!(tokens.next(), Some(&Token::Word("BEGIN")));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace(" ")));
-        assert_eq!(tokens.next(), Some(&Token::Word("TREES")));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace("   \t  ")));
-        assert_eq!(tokens.next(), Some(&Token::Word("word")));
-        assert_eq!(tokens.next(), Some(&Token::Comment("some comment()")));
-        assert_eq!(tokens.next(), Some(&Token::Word("other_word")));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Word("#NEXUS")));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Whitespace("\n")));
+        assert_eq!(tokens.next(), Some(Token::Word("BEGIN")));
+        assert_eq!(tokens.next(), Some(Token::Whitespace(" ")));
+        assert_eq!(tokens.next(), Some(Token::Word("TREES")));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Whitespace("   \t  ")));
+        assert_eq!(tokens.next(), Some(Token::Word("word")));
+        assert_eq!(tokens.next(), Some(Token::Comment("some comment()")));
+        assert_eq!(tokens.next(), Some(Token::Word("other_word")));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+
+        let lexer = Lexer::new("[&R] (A[&rate=1.5]:1,B)");
+        let mut tokens = Tokens::new(&lexer);
+
+        assert_eq!(tokens.next(), Some(Token::Annotation("R")));
+        assert_eq!(tokens.next(), Some(Token::Whitespace(" ")));
+        assert_eq!(tokens.next(), Some(Token::Punctuation("(")));
+        assert_eq!(tokens.next(), Some(Token::Word("A")));
+        assert_eq!(tokens.next(), Some(Token::Annotation("rate=1.5")));
+        assert_eq!(tokens.next(), Some(Token::Punctuation(":")));
 
         let lexer = Lexer::new(
             "#NEXUS
@@ -107,25 +157,34 @@ End;",
         );
         let mut tokens = Tokens::new(&lexer);
 
-        assert_eq!(tokens.next(), Some(&Token::Word("#NEXUS")));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace("\n")));
-        assert_eq!(tokens.next(), Some(&Token::Word("Begin")));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace(" ")));
-        assert_eq!(tokens.next(), Some(&Token::Word("Taxa")));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace("\n")));
-        assert_eq!(tokens.next(), Some(&Token::Word("Dimensions")));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace(" ")));
-        assert_eq!(tokens.next(), Some(&Token::Word("NTax")));
-        assert_eq!(tokens.next(), Some(&Token::Punctuation("=")));
-        assert_eq!(tokens.next(), Some(&Token::Integer(4)));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace("\n")));
-        assert_eq!(tokens.next(), Some(&Token::Word("End")));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
-        assert_eq!(tokens.next(), Some(&Token::Whitespace("\n")));
-        assert_eq!(tokens.next(), Some(&Token::Word("End")));
-        assert_eq!(tokens.next(), Some(&Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Word("#NEXUS")));
+        assert_eq!(tokens.next(), Some(Token::Whitespace("\n")));
+        assert_eq!(tokens.next(), Some(Token::Word("Begin")));
+        assert_eq!(tokens.next(), Some(Token::Whitespace(" ")));
+        assert_eq!(tokens.next(), Some(Token::Word("Taxa")));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Whitespace("\n")));
+        assert_eq!(tokens.next(), Some(Token::Word("Dimensions")));
+        assert_eq!(tokens.next(), Some(Token::Whitespace(" ")));
+        assert_eq!(tokens.next(), Some(Token::Word("NTax")));
+        assert_eq!(tokens.next(), Some(Token::Punctuation("=")));
+        assert_eq!(tokens.next(), Some(Token::Integer(4)));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Whitespace("\n")));
+        assert_eq!(tokens.next(), Some(Token::Word("End")));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+        assert_eq!(tokens.next(), Some(Token::Whitespace("\n")));
+        assert_eq!(tokens.next(), Some(Token::Word("End")));
+        assert_eq!(tokens.next(), Some(Token::EOS));
+        assert_eq!(tokens.next(), None);
+        assert_eq!(tokens.error(), None);
+
+        let lexer = Lexer::new("A 'unterminated");
+        let mut tokens = Tokens::new(&lexer);
+
+        assert_eq!(tokens.next(), Some(Token::Word("A")));
+        assert_eq!(tokens.next(), Some(Token::Whitespace(" ")));
         assert_eq!(tokens.next(), None);
+        assert_eq!(tokens.error(), Some(&LexingError { offset: 2 }));
     }
 }
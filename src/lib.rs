@@ -1,30 +1,67 @@
-use pyo3::prelude::*;
-use std::fs;
+use std::path::Path;
 
-pub use lexer::{lexer::Lexer, tokens::Tokens};
-pub use parser::parser::Parser;
-pub use types::nexus::{Nexus, NexusBlock};
+pub use analysis::{
+    clade_counts::{CladeCounts, CladeStats},
+    consensus::{ConsensusMethod, consensus},
+    convergence::{SplitFrequencies, SplitFrequency, split_frequencies},
+    distances::{
+        TreeDistance, branch_score, distance_matrix, normalized_robinson_foulds, quartet_distance,
+        robinson_foulds, triplet_distance, weighted_robinson_foulds,
+    },
+    mcc::{CladeCredibility, NodeHeights, TreeAnnotator, mcc_tree},
+    patristic::mean_patristic_distances,
+    statistics::TreeStatistics,
+    support::{SupportFormat, clade_support},
+    topologies::{TopologyCounts, TopologyStats},
+    tree_building::{DistanceMethod, SequenceDistance, SequenceDistanceError, build_tree},
+};
+pub use input::{Compression, InputError, NexusInput, open_decompressed};
+pub use lexer::{
+    lexer::{Lexer, LexingError},
+    tokens::Tokens,
+};
+pub use parser::{
+    alignment::{parse_fasta, parse_phylip},
+    parser::{Parser, ParsingError},
+    tree_reader::TreeReader,
+};
+pub use types::{
+    characters::{AlignmentOptions, CharacterMatrix, DataType, PhylipFormat},
+    clades::{Clade, Topology},
+    network::{Network, NetworkEdge, NetworkNode},
+    nexus::{Nexus, NexusBlock},
+    operations::RerootError,
+    taxa::{Taxa, TaxonId},
+    time::{TipDateError, parse_tip_dates},
+    tree::{AttributeValue, Attributes, Tree, TreeNode},
+};
+pub use writer::{
+    alignment::{write_fasta, write_phylip},
+    newick::write_newick,
+    nexml::write_nexml,
+    nexus::write_nexus,
+    phyloxml::write_phyloxml,
+};
 
+mod analysis;
+mod input;
 mod lexer;
 mod parser;
+#[cfg(feature = "python")]
+mod python;
 mod types;
+mod writer;
 
-#[pyfunction]
-pub fn parse_file(path: &str) {
-    let contents = fs::read_to_string(path).expect("Should have been able to read the file");
-
-    let lexer = Lexer::new(&contents);
-    let tokens = Tokens::new(&lexer);
-
-    let mut parser = Parser::new(tokens);
-    let result = parser.parse().unwrap();
-
-    println!("{}", result.blocks.len());
+/// Parses a (possibly compressed) NEXUS file. Uncompressed files are
+/// memory-mapped, but compressed ones are decompressed into memory as a whole, so
+/// large compressed tree files are better read one tree at a time with a
+/// [`TreeReader`].
+pub fn parse_file(path: impl AsRef<Path>) -> Result<Nexus, InputError> {
+    NexusInput::open(path)?.parse()
 }
 
-#[pymodule]
-fn nexus(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_function(wrap_pyfunction!(parse_file, m)?)?;
-
-    Ok(())
+/// Parses a (possibly compressed) plain Newick file with one tree per statement.
+/// As with [`parse_file`], compressed files are decompressed into memory.
+pub fn parse_newick_file(path: impl AsRef<Path>) -> Result<Nexus, InputError> {
+    NexusInput::open(path)?.parse_newick()
 }
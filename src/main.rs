@@ -1,10 +1,481 @@
-// use nexus::parse_file;
-
-fn main() {
-    // for _ in 1..10 {
-    //     parse_file(
-    //         // "/Users/tobiaochsner/Downloads/trees/bouckaert_et_al2018/original/pny10.fixed.cov.ucln.bdsky.ba-sp.trees",
-    //         "/Users/tobiaochsner/Documents/Thesis/Validation/data/mcmc_runs/yule-50_98.trees",
-    //     );
-    // }
+use std::{env, fs, process::ExitCode};
+
+use nexus::{
+    AlignmentOptions, CharacterMatrix, CladeCredibility, DistanceMethod, Lexer, Nexus, NexusBlock,
+    NexusInput, NodeHeights, Parser, ParsingError, PhylipFormat, SequenceDistance, Tokens, Tree,
+    build_tree, mcc_tree, parse_fasta, parse_phylip, split_frequencies, write_fasta, write_newick,
+    write_nexml, write_nexus, write_phylip, write_phyloxml,
+};
+
+const USAGE: &str = "Usage:
+    nexus-cli info <FILE>...
+    nexus-cli validate <FILE>...
+    nexus-cli convert <FILE> [--to <FORMAT>] [--strict] [--interleaved] [--output <FILE>]
+    nexus-cli extract <FILE> (--name <NAME> | --index <INDEX>)... [--to <FORMAT>] [--output <FILE>]
+    nexus-cli mcc <FILE> [--burnin <TREES>] [--heights <HEIGHTS>] [--to <FORMAT>] [--output <FILE>]
+    nexus-cli build <FILE> [--method <METHOD>] [--distance <DISTANCE>] [--to <FORMAT>] [--output <FILE>]
+    nexus-cli asdsf <FILE> <FILE>... [--burnin <TREES>] [--min-frequency <FREQUENCY>] [--output <FILE>]
+
+Input files are NEXUS, plain Newick files with one tree per statement, relaxed PHYLIP
+or FASTA alignments.
+Formats: nexus (default), newick, phylip, fasta, nexml, phyloxml (single trees only)
+PHYLIP is written relaxed and sequential unless --strict or --interleaved is given.
+Tree indices start at 0 and count the trees of all trees blocks in order.
+mcc writes the maximum clade credibility tree of the trees after the burn-in, annotated
+like TreeAnnotator does. Heights: keep (default), mean, median.
+build makes a tree from the distances between the sequences of a nucleotide alignment.
+Methods: nj (default), bionj, upgma. Distances: p, jc69 (default), k80.
+asdsf compares the split frequencies of independent runs with the same taxa, ignoring
+splits below the minimum frequency (default 0.1) in all runs, and writes the frequencies
+of every split to the output file.";
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Format {
+    Nexus,
+    Newick,
+    Phylip,
+    Fasta,
+    Nexml,
+    Phyloxml,
+}
+
+#[derive(Debug)]
+struct Options {
+    files: Vec<String>,
+    format: Format,
+    phylip: PhylipFormat,
+    output: Option<String>,
+    names: Vec<String>,
+    indices: Vec<usize>,
+    burnin: usize,
+    heights: NodeHeights,
+    min_frequency: f64,
+    method: DistanceMethod,
+    distance: SequenceDistance,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("info") => parse_options(&args[1..]).and_then(|options| info(&options)),
+        Some("validate") => parse_options(&args[1..]).and_then(|options| validate(&options)),
+        Some("convert") => parse_options(&args[1..]).and_then(|options| convert(&options)),
+        Some("extract") => parse_options(&args[1..]).and_then(|options| extract(&options)),
+        Some("mcc") => parse_options(&args[1..]).and_then(|options| mcc(&options)),
+        Some("build") => parse_options(&args[1..]).and_then(|options| build(&options)),
+        Some("asdsf") => parse_options(&args[1..]).and_then(|options| asdsf(&options)),
+        Some("help" | "-h" | "--help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => Err(format!("Unknown command.\n\n{}", USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        files: vec![],
+        format: Format::Nexus,
+        phylip: PhylipFormat::default(),
+        output: None,
+        names: vec![],
+        indices: vec![],
+        burnin: 0,
+        heights: NodeHeights::Keep,
+        min_frequency: 0.1,
+        method: DistanceMethod::NeighborJoining,
+        distance: SequenceDistance::Jc69,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {}.", arg))
+        };
+
+        match arg.as_str() {
+            "--to" | "-t" => {
+                options.format = match value()?.to_lowercase().as_str() {
+                    "nexus" | "nex" => Format::Nexus,
+                    "newick" | "nwk" => Format::Newick,
+                    "phylip" | "phy" => Format::Phylip,
+                    "fasta" | "fa" => Format::Fasta,
+                    "nexml" => Format::Nexml,
+                    "phyloxml" => Format::Phyloxml,
+                    format => return Err(format!("Unknown format {}.", format)),
+                }
+            }
+            "--strict" => options.phylip.strict = true,
+            "--interleaved" => options.phylip.interleaved = true,
+            "--output" | "-o" => options.output = Some(value()?.clone()),
+            "--name" | "-n" => options.names.push(value()?.clone()),
+            "--index" | "-i" => {
+                let index = value()?;
+                options.indices.push(
+                    index
+                        .parse()
+                        .map_err(|_| format!("Invalid tree index {}.", index))?,
+                );
+            }
+            "--burnin" | "-b" => {
+                let burnin = value()?;
+                options.burnin = burnin
+                    .parse()
+                    .map_err(|_| format!("Invalid burn-in {}.", burnin))?;
+            }
+            "--heights" => {
+                options.heights = match value()?.to_lowercase().as_str() {
+                    "keep" => NodeHeights::Keep,
+                    "mean" => NodeHeights::Mean,
+                    "median" => NodeHeights::Median,
+                    heights => return Err(format!("Unknown heights {}.", heights)),
+                }
+            }
+            "--min-frequency" => {
+                let min_frequency = value()?;
+                options.min_frequency = min_frequency
+                    .parse()
+                    .map_err(|_| format!("Invalid minimum frequency {}.", min_frequency))?;
+            }
+            "--method" => {
+                options.method = match value()?.to_lowercase().as_str() {
+                    "nj" => DistanceMethod::NeighborJoining,
+                    "bionj" => DistanceMethod::Bionj,
+                    "upgma" => DistanceMethod::Upgma,
+                    method => return Err(format!("Unknown method {}.", method)),
+                }
+            }
+            "--distance" => {
+                options.distance = match value()?.to_lowercase().as_str() {
+                    "p" => SequenceDistance::P,
+                    "jc69" => SequenceDistance::Jc69,
+                    "k80" => SequenceDistance::K80,
+                    distance => return Err(format!("Unknown distance {}.", distance)),
+                }
+            }
+            option if option.starts_with('-') => {
+                return Err(format!("Unknown option {}.\n\n{}", option, USAGE));
+            }
+            file => options.files.push(file.to_string()),
+        }
+    }
+
+    if options.files.is_empty() {
+        return Err(format!("Missing input file.\n\n{}", USAGE));
+    }
+
+    Ok(options)
+}
+
+/// Parses the content as plain Newick if it starts with a tree, as FASTA if it
+/// starts with a sequence name, as PHYLIP if it starts with the dimensions and as
+/// NEXUS otherwise.
+fn parse(parser: &mut Parser, content: &str) -> Result<Nexus, ParsingError> {
+    let options = AlignmentOptions::default();
+
+    match content.trim_start().chars().next() {
+        Some('(') => parser.parse_newick(),
+        Some('>') => parse_fasta(content, options),
+        Some(c) if c.is_ascii_digit() => parse_phylip(content, PhylipFormat::default(), options)
+            .or_else(|_| {
+                let interleaved = PhylipFormat {
+                    interleaved: true,
+                    ..Default::default()
+                };
+                parse_phylip(content, interleaved, options)
+            }),
+        _ => parser.parse(),
+    }
+}
+
+fn read(file: &str) -> Result<Nexus, String> {
+    let input = NexusInput::open(file).map_err(|error| format!("{}: {}", file, error))?;
+    let content = input
+        .as_str()
+        .map_err(|error| format!("{}: {}", file, error))?;
+
+    let lexer = Lexer::new(content);
+    let mut parser = Parser::new(Tokens::new(&lexer));
+
+    parse(&mut parser, content).map_err(|error| format!("{}: {}", file, error))
+}
+
+fn trees(nexus: &Nexus) -> impl Iterator<Item = &Tree> {
+    nexus.blocks.iter().flat_map(|block| match block {
+        NexusBlock::TreesBlock(_, trees) => trees.as_slice(),
+        _ => &[],
+    })
+}
+
+fn matrix(nexus: &Nexus) -> Option<&CharacterMatrix> {
+    nexus.blocks.iter().find_map(|block| match block {
+        NexusBlock::CharactersBlock(matrix) => Some(matrix),
+        _ => None,
+    })
+}
+
+fn info(options: &Options) -> Result<(), String> {
+    for file in &options.files {
+        let nexus = read(file)?;
+
+        println!("{}", file);
+        for block in &nexus.blocks {
+            match block {
+                NexusBlock::TaxaBlock(dimensions, _) => {
+                    println!("  TAXA block: {} taxa", dimensions)
+                }
+                NexusBlock::TreesBlock(translations, trees) => println!(
+                    "  TREES block: {} trees, {} translations",
+                    trees.len(),
+                    translations.len()
+                ),
+                NexusBlock::CharactersBlock(matrix) => println!(
+                    "  CHARACTERS block: {} taxa, {} characters ({})",
+                    matrix.sequences.len(),
+                    matrix.nchar(),
+                    matrix.datatype
+                ),
+            }
+        }
+        println!("  Taxa: {}", nexus.taxa.len());
+        println!("  Trees: {}", trees(&nexus).count());
+    }
+
+    Ok(())
+}
+
+/// Returns the 1-based line and column of a byte offset.
+fn locate(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
+
+fn validate(options: &Options) -> Result<(), String> {
+    let mut invalid_files = 0;
+
+    for file in &options.files {
+        let input = NexusInput::open(file).map_err(|error| format!("{}: {}", file, error))?;
+        let content = input
+            .as_str()
+            .map_err(|error| format!("{}: {}", file, error))?;
+
+        let lexer = Lexer::new(content);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        match parse(&mut parser, content) {
+            Ok(nexus) => println!(
+                "{}: valid ({} blocks, {} taxa, {} trees)",
+                file,
+                nexus.blocks.len(),
+                nexus.taxa.len(),
+                trees(&nexus).count()
+            ),
+            Err(error) => {
+                // the PHYLIP and FASTA readers do not locate their errors
+                match parser.error_position() {
+                    Some(position) => {
+                        let (line, column) = locate(content, position);
+                        println!("{}:{}:{}: error: {}", file, line, column, error);
+                    }
+                    None => println!("{}: error: {}", file, error),
+                }
+                invalid_files += 1;
+            }
+        }
+    }
+
+    match invalid_files {
+        0 => Ok(()),
+        _ => Err(format!("{} invalid file(s).", invalid_files)),
+    }
+}
+
+fn write(nexus: &Nexus, options: &Options) -> Result<(), String> {
+    let content = match options.format {
+        Format::Nexus => write_nexus(nexus),
+        Format::Newick => trees(nexus)
+            .map(|tree| write_newick(tree, &nexus.taxa) + "\n")
+            .collect(),
+        Format::Nexml => write_nexml(nexus),
+        Format::Phyloxml => match trees(nexus).collect::<Vec<_>>().as_slice() {
+            [tree] => write_phyloxml(tree, &nexus.taxa),
+            trees => {
+                return Err(format!(
+                    "PhyloXML holds a single tree, but there are {} trees.",
+                    trees.len()
+                ));
+            }
+        },
+        Format::Phylip | Format::Fasta => {
+            let matrix = matrix(nexus).ok_or("There is no character matrix to write.")?;
+            match options.format {
+                Format::Phylip => write_phylip(matrix, &nexus.taxa, options.phylip),
+                _ => write_fasta(matrix, &nexus.taxa),
+            }
+        }
+    };
+
+    match &options.output {
+        Some(output) => {
+            fs::write(output, content).map_err(|error| format!("{}: {}", output, error))
+        }
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
+}
+
+fn convert(options: &Options) -> Result<(), String> {
+    let [file] = options.files.as_slice() else {
+        return Err(format!("Expected exactly one input file.\n\n{}", USAGE));
+    };
+
+    write(&read(file)?, options)
+}
+
+fn extract(options: &Options) -> Result<(), String> {
+    let [file] = options.files.as_slice() else {
+        return Err(format!("Expected exactly one input file.\n\n{}", USAGE));
+    };
+    if options.names.is_empty() && options.indices.is_empty() {
+        return Err(format!("Expected --name or --index.\n\n{}", USAGE));
+    }
+
+    let nexus = read(file)?;
+    let all_trees: Vec<&Tree> = trees(&nexus).collect();
+
+    let mut indices = vec![];
+    for name in &options.names {
+        match all_trees.iter().position(|tree| &tree.name == name) {
+            Some(index) => indices.push(index),
+            None => return Err(format!("{}: there is no tree named {}.", file, name)),
+        }
+    }
+    for index in &options.indices {
+        if *index >= all_trees.len() {
+            return Err(format!(
+                "{}: there is no tree with index {} ({} trees).",
+                file,
+                index,
+                all_trees.len()
+            ));
+        }
+        indices.push(*index);
+    }
+
+    // a tree selected twice is only written once
+    let mut extracted_trees = vec![];
+    for (i, index) in indices.iter().enumerate() {
+        if !indices[..i].contains(index) {
+            extracted_trees.push(all_trees[*index].clone());
+        }
+    }
+
+    write(&with_trees(file, &nexus, extracted_trees)?, options)
+}
+
+fn mcc(options: &Options) -> Result<(), String> {
+    let [file] = options.files.as_slice() else {
+        return Err(format!("Expected exactly one input file.\n\n{}", USAGE));
+    };
+
+    let nexus = read(file)?;
+    let sample: Vec<Tree> = trees(&nexus).skip(options.burnin).cloned().collect();
+    let tree = mcc_tree(
+        &sample,
+        nexus.taxa.len(),
+        CladeCredibility::Product,
+        options.heights,
+    )
+    .ok_or_else(|| format!("{}: there are no trees after the burn-in.", file))?;
+
+    write(&with_trees(file, &nexus, vec![tree])?, options)
+}
+
+fn build(options: &Options) -> Result<(), String> {
+    let [file] = options.files.as_slice() else {
+        return Err(format!("Expected exactly one input file.\n\n{}", USAGE));
+    };
+
+    let nexus = read(file)?;
+    let matrix = matrix(&nexus).ok_or_else(|| format!("{}: there is no alignment.", file))?;
+    let distances = matrix
+        .distances(nexus.taxa.len(), options.distance)
+        .map_err(|error| format!("{}: {}.", file, error))?;
+
+    write(
+        &with_trees(file, &nexus, vec![build_tree(&distances, options.method)])?,
+        options,
+    )
+}
+
+fn asdsf(options: &Options) -> Result<(), String> {
+    if options.files.len() < 2 {
+        return Err(format!("Expected at least two input files.\n\n{}", USAGE));
+    }
+
+    let runs = options
+        .files
+        .iter()
+        .map(|file| read(file))
+        .collect::<Result<Vec<_>, _>>()?;
+    let taxa = &runs[0].taxa;
+    for (file, run) in options.files.iter().zip(&runs) {
+        if run.taxa.labels() != taxa.labels() {
+            return Err(format!(
+                "{}: the taxa differ from the ones of {}.",
+                file, options.files[0]
+            ));
+        }
+    }
+
+    let frequencies = split_frequencies(
+        runs.iter().map(trees),
+        taxa.len(),
+        options.burnin,
+        options.min_frequency,
+    );
+    let format = |value: Option<f64>| value.map_or(String::from("NA"), |value| value.to_string());
+    println!("Trees after burn-in: {:?}", frequencies.tree_counts());
+    println!("ASDSF: {}", format(frequencies.asdsf()));
+    println!("Max SDSF: {}", format(frequencies.max_sdsf()));
+
+    match &options.output {
+        Some(output) => fs::write(output, frequencies.to_tsv(taxa))
+            .map_err(|error| format!("{}: {}", output, error)),
+        None => Ok(()),
+    }
+}
+
+/// A Nexus with the TAXA blocks of the given one and a single trees block, which
+/// fails if two of the trees have the same name.
+fn with_trees(file: &str, nexus: &Nexus, trees: Vec<Tree>) -> Result<Nexus, String> {
+    let mut blocks: Vec<NexusBlock> = nexus
+        .blocks
+        .iter()
+        .filter(|block| matches!(block, NexusBlock::TaxaBlock(..)))
+        .cloned()
+        .collect();
+    blocks.push(
+        NexusBlock::build_trees_block(Default::default(), trees)
+            .map_err(|error| format!("{}: {}.", file, error))?,
+    );
+
+    Ok(Nexus {
+        blocks,
+        taxa: nexus.taxa.clone(),
+    })
 }
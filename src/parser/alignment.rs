@@ -0,0 +1,130 @@
+use crate::{
+    parser::parser::ParsingError,
+    types::{
+        characters::{AlignmentOptions, CharacterMatrix, DataType, PhylipFormat, character_count},
+        nexus::{Nexus, NexusBlock},
+        taxa::Taxa,
+    },
+};
+
+/// Parses a PHYLIP alignment into a Nexus with a TAXA and a CHARACTERS block.
+pub fn parse_phylip(
+    content: &str,
+    format: PhylipFormat,
+    options: AlignmentOptions,
+) -> Result<Nexus, ParsingError> {
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+
+    let header = lines.next().ok_or(ParsingError::UnexpectedFileEnd)?;
+    let mut dimensions = header
+        .split_whitespace()
+        .map(|dimension| dimension.parse::<usize>());
+    let (Some(Ok(ntax)), Some(Ok(nchar))) = (dimensions.next(), dimensions.next()) else {
+        return Err(ParsingError::InvalidNumber);
+    };
+
+    let mut rows = vec![];
+    for _ in 0..ntax {
+        let line = lines.next().ok_or(ParsingError::UnexpectedFileEnd)?;
+        let (name, sequence) = split_phylip_name(line, format.strict);
+        let mut sequence = without_whitespace(sequence);
+
+        // sequential files may wrap a sequence over several lines
+        while !format.interleaved && character_count(&sequence) < nchar {
+            let line = lines.next().ok_or(ParsingError::UnexpectedFileEnd)?;
+            sequence.push_str(&without_whitespace(line));
+        }
+
+        rows.push((name.to_string(), sequence));
+    }
+
+    if format.interleaved {
+        for (i, line) in lines.enumerate() {
+            rows[i % ntax].1.push_str(&without_whitespace(line));
+        }
+    } else if lines.next().is_some() {
+        return Err(ParsingError::CharactersDimensionsMismatch);
+    }
+
+    build_alignment(rows, Some(nchar), options)
+}
+
+/// Parses a FASTA alignment into a Nexus with a TAXA and a CHARACTERS block. The
+/// whole header line is used as the name of a taxon.
+pub fn parse_fasta(content: &str, options: AlignmentOptions) -> Result<Nexus, ParsingError> {
+    let mut rows: Vec<(String, String)> = vec![];
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        match line.strip_prefix('>') {
+            Some(name) => rows.push((name.trim().to_string(), String::new())),
+            None => match rows.last_mut() {
+                Some((_, sequence)) => sequence.push_str(&without_whitespace(line)),
+                None => return Err(ParsingError::MissingToken(String::from(">"))),
+            },
+        }
+    }
+
+    build_alignment(rows, None, options)
+}
+
+fn split_phylip_name(line: &str, strict: bool) -> (&str, &str) {
+    if strict {
+        let end = line
+            .char_indices()
+            .nth(10)
+            .map_or(line.len(), |(index, _)| index);
+        (line[..end].trim(), &line[end..])
+    } else {
+        let line = line.trim_start();
+        line.split_once(char::is_whitespace).unwrap_or((line, ""))
+    }
+}
+
+fn without_whitespace(sequence: &str) -> String {
+    sequence
+        .chars()
+        .filter(|state| !state.is_whitespace())
+        .collect()
+}
+
+fn build_alignment(
+    rows: Vec<(String, String)>,
+    nchar: Option<usize>,
+    options: AlignmentOptions,
+) -> Result<Nexus, ParsingError> {
+    let taxa = Taxa::from_iter(rows.iter().map(|(name, _)| name));
+    if taxa.len() != rows.len() {
+        return Err(ParsingError::DuplicateTaxa);
+    }
+
+    let datatype = options.datatype.unwrap_or_else(|| {
+        DataType::infer(
+            rows.iter().map(|(_, sequence)| sequence.as_str()),
+            options.missing,
+            options.gap,
+        )
+    });
+    let nchar = nchar.unwrap_or_else(|| {
+        rows.first()
+            .map_or(0, |(_, sequence)| character_count(sequence))
+    });
+
+    let mut matrix = CharacterMatrix::new(datatype)
+        .with_missing(options.missing)
+        .with_gap(options.gap);
+    matrix.sequences = rows
+        .into_iter()
+        .enumerate()
+        .map(|(taxon, (_, sequence))| (taxon, sequence))
+        .collect();
+
+    let blocks = vec![
+        NexusBlock::build_taxa_block(taxa.len(), taxa.labels().to_vec())?,
+        NexusBlock::build_characters_block(None, nchar, matrix)?,
+    ];
+    Nexus::build(blocks, taxa)
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::{
+            alignment::{parse_fasta, parse_phylip},
+            parser::{Parser, ParsingError},
+        },
+        types::{
+            characters::{AlignmentOptions, DataType, PhylipFormat},
+            nexus::{Nexus, NexusBlock},
+        },
+        writer::{
+            alignment::{write_fasta, write_phylip},
+            nexus::write_nexus,
+        },
+    };
+
+    const ALIGNMENT: &str = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=3;
+            TAXLABELS Gorilla Homo_sapiens Pan;
+        END;
+        BEGIN CHARACTERS;
+            DIMENSIONS NCHAR=8;
+            FORMAT DATATYPE=DNA MISSING=? GAP=-;
+            MATRIX
+                Pan          ACGT-CGA
+                Gorilla      ACGTTC?A
+                Homo_sapiens AC-TTCGA
+            ;
+        END;
+        ";
+
+    fn parse(text: &str) -> Nexus {
+        let lexer = Lexer::new(text);
+        Parser::new(Tokens::new(&lexer)).parse().unwrap()
+    }
+
+    fn matrix(nexus: &Nexus) -> &crate::types::characters::CharacterMatrix {
+        nexus
+            .blocks
+            .iter()
+            .find_map(|block| match block {
+                NexusBlock::CharactersBlock(matrix) => Some(matrix),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_phylip_round_trip() {
+        let nexus = parse(ALIGNMENT);
+
+        for strict in [false, true] {
+            for interleaved in [false, true] {
+                let format = PhylipFormat {
+                    strict,
+                    interleaved,
+                };
+                let phylip = write_phylip(matrix(&nexus), &nexus.taxa, format);
+                let result = parse_phylip(&phylip, format, AlignmentOptions::default()).unwrap();
+
+                // strict PHYLIP truncates names to 10 characters
+                let names: Vec<&str> = match strict {
+                    true => vec!["Pan", "Gorilla", "Homo_sapie"],
+                    false => vec!["Pan", "Gorilla", "Homo_sapiens"],
+                };
+                assert_eq!(result.taxa.labels(), names.as_slice());
+                assert_eq!(matrix(&result).datatype, DataType::Dna);
+                assert_eq!(
+                    matrix(&result).sequences,
+                    vec![
+                        (0, "ACGT-CGA".to_string()),
+                        (1, "ACGTTC?A".to_string()),
+                        (2, "AC-TTCGA".to_string()),
+                    ]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_nexus_phylip_nexus_keeps_matrix() {
+        let nexus = parse(ALIGNMENT);
+        let options = AlignmentOptions::default();
+
+        let phylip = write_phylip(matrix(&nexus), &nexus.taxa, PhylipFormat::default());
+        let result = parse(&write_nexus(
+            &parse_phylip(&phylip, PhylipFormat::default(), options).unwrap(),
+        ));
+
+        assert_eq!(result.taxa.labels(), &["Pan", "Gorilla", "Homo_sapiens"]);
+        let mut expected = matrix(&nexus).clone();
+        expected.sequences = vec![
+            (0, "ACGT-CGA".to_string()),
+            (1, "ACGTTC?A".to_string()),
+            (2, "AC-TTCGA".to_string()),
+        ];
+        assert_eq!(matrix(&result), &expected);
+    }
+
+    #[test]
+    fn test_nexus_round_trip_keeps_symbols() {
+        let text = ALIGNMENT
+            .replace("MISSING=? GAP=-", "MISSING=N GAP=.")
+            .replace('?', "N")
+            .replace('-', ".");
+        let nexus = parse(&text);
+
+        assert_eq!(parse(&write_nexus(&nexus)), nexus);
+    }
+
+    #[test]
+    fn test_fasta_round_trip() {
+        let nexus = parse(ALIGNMENT);
+        let options = AlignmentOptions {
+            datatype: Some(DataType::Dna),
+            ..Default::default()
+        };
+
+        let fasta = write_fasta(matrix(&nexus), &nexus.taxa);
+        assert!(fasta.starts_with(">Pan\nACGT-CGA\n>Gorilla\n"));
+
+        let result = parse_fasta(&fasta, options).unwrap();
+        assert_eq!(matrix(&result).sequences.len(), 3);
+        assert_eq!(matrix(&result).sequence(2), Some("AC-TTCGA"));
+    }
+
+    #[test]
+    fn test_wrapped_sequential_phylip() {
+        let phylip = "2 6\nalpha AAC\nGTT\nbeta  MKL\nVVI\n";
+        let result =
+            parse_phylip(phylip, PhylipFormat::default(), AlignmentOptions::default()).unwrap();
+
+        assert_eq!(matrix(&result).datatype, DataType::Protein);
+        assert_eq!(matrix(&result).sequence(1), Some("MKLVVI"));
+    }
+
+    #[test]
+    fn test_invalid_alignments() {
+        let options = AlignmentOptions::default();
+
+        assert_eq!(
+            parse_fasta("ACGT\n>a\nACGT\n", options),
+            Err(ParsingError::MissingToken(">".to_string()))
+        );
+        assert_eq!(
+            parse_fasta(">a\nACGT\n>b\nACG\n", options),
+            Err(ParsingError::CharactersDimensionsMismatch)
+        );
+        assert_eq!(
+            parse_fasta(">a\nACGT\n>a\nACGT\n", options),
+            Err(ParsingError::DuplicateTaxa)
+        );
+        assert_eq!(
+            parse_phylip("2\na ACGT\n", PhylipFormat::default(), options),
+            Err(ParsingError::InvalidNumber)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crate::{
+    parser::parser::ParsingError,
+    types::tree::{AttributeValue, Attributes},
+};
+
+/// Parses the content of a FigTree/BEAST annotation such as
+/// `rate=1.2,height_95%_HPD={0.5,0.9},set="A B"`. Values are numbers, optionally
+/// double-quoted text or lists in braces; keys without a value are ignored.
+pub(crate) fn parse_attributes(annotation: &str) -> Result<Attributes, ParsingError> {
+    let mut attributes = Attributes::new();
+    let mut chars = annotation.char_indices().peekable();
+
+    while chars.peek().is_some() {
+        let key = take_until(annotation, &mut chars, |c| c == '=' || c == ',');
+        let key = key.trim();
+
+        if let Some((_, '=')) = chars.next() {
+            let value = parse_value(annotation, &mut chars)?;
+            if key.is_empty() {
+                return Err(ParsingError::MalformedAnnotation);
+            }
+            attributes.insert(key.to_string(), value);
+
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some((_, ',')) | None => {}
+                Some(_) => return Err(ParsingError::MalformedAnnotation),
+            }
+        }
+    }
+
+    Ok(attributes)
+}
+
+fn parse_value(
+    annotation: &str,
+    chars: &mut Peekable<CharIndices>,
+) -> Result<AttributeValue, ParsingError> {
+    skip_whitespace(chars);
+
+    match chars.peek() {
+        Some((_, '{')) => {
+            chars.next();
+            let mut values = vec![];
+            skip_whitespace(chars);
+            if let Some((_, '}')) = chars.peek() {
+                chars.next();
+                return Ok(AttributeValue::List(values));
+            }
+
+            loop {
+                values.push(parse_value(annotation, chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some((_, ',')) => {}
+                    Some((_, '}')) => return Ok(AttributeValue::List(values)),
+                    _ => return Err(ParsingError::MalformedAnnotation),
+                }
+            }
+        }
+        Some((_, quote @ ('"' | '\''))) => {
+            let quote = *quote;
+            chars.next();
+            let text = take_until(annotation, chars, |c| c == quote);
+            match chars.next() {
+                Some(_) => Ok(AttributeValue::Text(text.to_string())),
+                None => Err(ParsingError::MalformedAnnotation),
+            }
+        }
+        _ => {
+            let text = take_until(annotation, chars, |c| c == ',' || c == '}').trim();
+            Ok(match text.parse::<f64>() {
+                Ok(number) => AttributeValue::Number(number),
+                Err(_) => AttributeValue::Text(text.to_string()),
+            })
+        }
+    }
+}
+
+/// Consumes the characters up to, but not including, the first one matching the
+/// predicate and returns them.
+fn take_until<'a>(
+    annotation: &'a str,
+    chars: &mut Peekable<CharIndices>,
+    predicate: impl Fn(char) -> bool,
+) -> &'a str {
+    let start = chars.peek().map_or(annotation.len(), |(index, _)| *index);
+    while chars.next_if(|(_, c)| !predicate(*c)).is_some() {}
+    let end = chars.peek().map_or(annotation.len(), |(index, _)| *index);
+    &annotation[start..end]
+}
+
+fn skip_whitespace(chars: &mut Peekable<CharIndices>) {
+    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::{
+            annotation::parse_attributes,
+            parser::{Parser, ParsingError},
+        },
+        types::{
+            nexus::NexusBlock,
+            tree::{AttributeValue, Tree},
+        },
+        writer::{newick::write_newick, nexus::write_nexus},
+    };
+
+    fn parse_trees(text: &str) -> (crate::types::nexus::Nexus, Vec<Tree>) {
+        let lexer = Lexer::new(text);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse().unwrap();
+        let trees = nexus
+            .blocks
+            .iter()
+            .find_map(|block| match block {
+                NexusBlock::TreesBlock(_, trees) => Some(trees.clone()),
+                _ => None,
+            })
+            .unwrap();
+        (nexus, trees)
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let attributes =
+            parse_attributes(r#"rate=1.5,height_95%_HPD={0.5, 2},set={"A B",C},note=x,R"#).unwrap();
+
+        assert_eq!(attributes["rate"], AttributeValue::Number(1.5));
+        assert_eq!(
+            attributes["height_95%_HPD"],
+            AttributeValue::List(vec![
+                AttributeValue::Number(0.5),
+                AttributeValue::Number(2.0)
+            ])
+        );
+        assert_eq!(
+            attributes["set"],
+            AttributeValue::List(vec![
+                AttributeValue::Text("A B".to_string()),
+                AttributeValue::Text("C".to_string())
+            ])
+        );
+        assert_eq!(attributes["note"], AttributeValue::Text("x".to_string()));
+        assert_eq!(attributes.len(), 4);
+
+        assert_eq!(
+            parse_attributes("rate={1,2"),
+            Err(ParsingError::MalformedAnnotation)
+        );
+        assert_eq!(
+            parse_attributes("name=\"A"),
+            Err(ParsingError::MalformedAnnotation)
+        );
+    }
+
+    #[test]
+    fn test_beast_annotations() {
+        let (nexus, trees) = parse_trees(
+            "#NEXUS
+            BEGIN TAXA;
+                DIMENSIONS NTAX=3;
+                TAXLABELS A B C;
+            END;
+            BEGIN TREES;
+                TRANSLATE 1 A, 2 B, 3 C;
+                TREE STATE_0 [&lnP=-1234.5] = [&R] ((1[&rate=0.5]:1.0,2[&rate=1.5]:1.0)[&rate=1]:0.5,3[&rate=2]:1.5);
+                TREE STATE_1 = ((1:1.0[&prob=0.9],2:1.0),3:1.5);
+            END;",
+        );
+
+        assert!(trees[0].rooted);
+        assert!(!trees[1].rooted);
+
+        let a = trees[0].leaf(0).unwrap();
+        assert_eq!(
+            trees[0].node(a).attributes().get("rate"),
+            Some(&AttributeValue::Number(0.5))
+        );
+        assert_eq!(trees[0].node(a).length(), Some(1.0));
+        let a_b = trees[0].parent(a).unwrap();
+        assert_eq!(
+            trees[0].node(a_b).attributes().get("rate"),
+            Some(&AttributeValue::Number(1.0))
+        );
+        assert_eq!(trees[0].node(a_b).length(), Some(0.5));
+
+        let a = trees[1].leaf(0).unwrap();
+        assert_eq!(
+            trees[1].node(a).attributes().get("prob"),
+            Some(&AttributeValue::Number(0.9))
+        );
+
+        assert_eq!(
+            write_newick(&trees[0], &nexus.taxa),
+            "[&R] ((A[&rate=0.5]:1,B[&rate=1.5]:1)[&rate=1]:0.5,C[&rate=2]:1.5);"
+        );
+        assert!(write_nexus(&nexus).contains("TREE STATE_0 = [&R] ((1[&rate=0.5]:1,"));
+    }
+
+    #[test]
+    fn test_rooted_newick() {
+        let lexer = Lexer::new("[&R] ((A,B),C);\n[&U] ((A,B),C);");
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+
+        let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.first() else {
+            panic!("Expected a trees block.");
+        };
+        assert!(trees[0].rooted);
+        assert!(!trees[1].rooted);
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::{Parser, ParsingError},
+        types::{
+            characters::{CharacterMatrix, DataType},
+            nexus::NexusBlock,
+        },
+    };
+
+    #[test]
+    fn test_characters_block() {
+        let text = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=3;
+            TAXLABELS Apes Humans 'Pan troglodytes';
+        END;
+        BEGIN CHARACTERS;
+            DIMENSIONS NCHAR=6;
+            FORMAT DATATYPE=DNA MISSING=N GAP=- INTERLEAVE;
+            MATRIX
+                Humans ACG
+                Apes   AC-
+                'Pan troglodytes' A{CT}G
+                Humans TTA
+                Apes   T TN
+                'Pan troglodytes' TTA
+            ;
+        END;
+        ";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let result = parser.parse().unwrap();
+
+        let mut matrix = CharacterMatrix::new(DataType::Dna).with_missing('N');
+        matrix.sequences = vec![
+            (1, "ACGTTA".to_string()),
+            (0, "AC-TTN".to_string()),
+            (2, "A{CT}GTTA".to_string()),
+        ];
+
+        assert_eq!(result.blocks[1], NexusBlock::CharactersBlock(matrix));
+    }
+
+    #[test]
+    fn test_data_block_defines_taxa() {
+        let text = "#NEXUS
+        BEGIN DATA;
+            DIMENSIONS NTAX=2 NCHAR=4;
+            FORMAT DATATYPE=Standard;
+            MATRIX
+                a 0101
+                b 1?10
+            ;
+        END;
+        ";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let result = parser.parse().unwrap();
+
+        assert_eq!(result.taxa.labels(), &["a", "b"]);
+        let mut matrix = CharacterMatrix::new(DataType::Standard);
+        matrix.sequences = vec![(0, "0101".to_string()), (1, "1?10".to_string())];
+        assert_eq!(result.blocks, vec![NexusBlock::CharactersBlock(matrix)]);
+    }
+
+    #[test]
+    fn test_wrapped_sequential_matrix() {
+        let text = "#NEXUS
+        BEGIN DATA;
+            DIMENSIONS NTAX=3 NCHAR=10;
+            FORMAT DATATYPE=DNA;
+            MATRIX
+                a ACGTA
+                  CGTAC
+                b ACGTA C{GT}TAC c ACG
+                  TACGTAC
+            ;
+        END;
+        ";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let result = parser.parse().unwrap();
+
+        // the continuation lines are not taxa
+        assert_eq!(result.taxa.labels(), &["a", "b", "c"]);
+        let mut matrix = CharacterMatrix::new(DataType::Dna);
+        matrix.sequences = vec![
+            (0, "ACGTACGTAC".to_string()),
+            (1, "ACGTAC{GT}TAC".to_string()),
+            (2, "ACGTACGTAC".to_string()),
+        ];
+        assert_eq!(result.blocks, vec![NexusBlock::CharactersBlock(matrix)]);
+    }
+
+    #[test]
+    fn test_characters_dimensions_mismatch() {
+        let text = "#NEXUS
+        BEGIN DATA;
+            DIMENSIONS NTAX=2 NCHAR=4;
+            MATRIX
+                a 0101
+                b 110
+            ;
+        END;
+        ";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        assert_eq!(
+            parser.parse(),
+            Err(ParsingError::CharactersDimensionsMismatch)
+        );
+    }
+}
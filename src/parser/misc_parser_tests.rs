@@ -6,25 +6,19 @@ mod tests {
         types::nexus::Nexus,
     };
 
-    impl<'a> Nexus {
-        pub fn new() -> Self {
-            Nexus { blocks: vec![] }
-        }
-    }
-
     #[test]
     fn test_empty_nexus() {
         let text = "#NEXUS";
         let lexer = Lexer::new(text);
         let tokens = Tokens::new(&lexer);
         let mut parser = Parser::new(tokens);
-        assert_eq!(parser.parse(), Ok(Nexus::new()));
+        assert_eq!(parser.parse(), Ok(Nexus::default()));
 
         let text = "#nexus";
         let lexer = Lexer::new(text);
         let tokens = Tokens::new(&lexer);
         let mut parser = Parser::new(tokens);
-        assert_eq!(parser.parse(), Ok(Nexus::new()));
+        assert_eq!(parser.parse(), Ok(Nexus::default()));
 
         let text = "#notnexus";
         let lexer = Lexer::new(text);
@@ -46,4 +40,103 @@ mod tests {
             Err(ParsingError::MissingToken(String::from("begin")))
         );
     }
+
+    #[test]
+    fn test_invalid_token() {
+        for text in [
+            "#NEXUS\nBegin taxa;\n  Dimensions ntax=1;\n  TaxLabels 'A;\nEnd;",
+            "#NEXUS\nBegin trees;\n  Tree t = (A,B);\nEnd; [#unterminated comment",
+        ] {
+            let lexer = Lexer::new(text);
+            let tokens = Tokens::new(&lexer);
+            let mut parser = Parser::new(tokens);
+            assert_eq!(parser.parse(), Err(ParsingError::InvalidToken));
+        }
+
+        let lexer = Lexer::new("(A,B[&rate=1);");
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        assert_eq!(parser.parse_newick(), Err(ParsingError::InvalidToken));
+    }
+
+    #[test]
+    fn test_error_position() {
+        let text = "#NEXUS
+        BEGIN taxa;
+            DIMENSIONS ntax=2;
+            TAXLABELS A B;
+        END;
+        BEGIN trees;
+            TREE t1 = (A,B);
+            TREE t2 = (A:x,B);
+        END;";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        assert_eq!(parser.error_position(), None);
+        assert_eq!(
+            parser.parse(),
+            Err(ParsingError::MissingToken(String::from(")")))
+        );
+        // the unparsable branch length
+        assert_eq!(parser.error_position(), Some(text.find(":x").unwrap()));
+    }
+
+    /// The 1-based line and column of the position of the last error.
+    fn error_line_and_column(parser: &Parser, text: &str) -> Option<(usize, usize)> {
+        let before = &text[..parser.error_position()?];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+        Some((line, column))
+    }
+
+    #[test]
+    fn test_no_error_position_after_success() {
+        // the blocks are recognized after trying other block names first
+        let text = "#NEXUS
+BEGIN data;
+    DIMENSIONS ntax=2 nchar=2;
+    MATRIX
+        A AC
+        B AG
+    ;
+END;
+BEGIN trees;
+    TREE t1 = (A:1,B:2);
+END;";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        assert!(parser.parse().is_ok());
+        assert_eq!(parser.error_position(), None);
+    }
+
+    #[test]
+    fn test_invalid_block_position() {
+        let text = "#NEXUS
+BEGIN taxa;
+    DIMENSIONS ntax=2;
+    TAXLABELS A B;
+END;
+BEGIN assumptions;
+END;";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        assert_eq!(parser.parse(), Err(ParsingError::InvalidBlock));
+        assert_eq!(error_line_and_column(&parser, text), Some((6, 7)));
+    }
+
+    #[test]
+    fn test_malformed_tree_position() {
+        let text = "#NEXUS
+BEGIN trees;
+    TREE t1 = (A,B);
+    TREE t2 = (A, );
+END;";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        assert_eq!(parser.parse(), Err(ParsingError::MalformedTreeStructure));
+        assert_eq!(error_line_and_column(&parser, text), Some((4, 19)));
+    }
 }
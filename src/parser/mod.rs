@@ -1,5 +1,14 @@
+pub mod alignment;
+mod alignment_tests;
+mod annotation;
+mod annotation_parser_tests;
+mod characters_parser_tests;
 mod misc_parser_tests;
+mod network_parser_tests;
+mod newick_parser_tests;
 pub mod parser;
 mod taxa_parser_tests;
 mod translations_parser_tests;
+pub mod tree_reader;
+mod tree_reader_tests;
 mod trees_parser_tests;
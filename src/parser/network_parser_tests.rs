@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::{Parser, ParsingError},
+        types::{
+            network::{NetworkEdge, NetworkNode},
+            nexus::NexusBlock,
+        },
+    };
+
+    #[test]
+    fn test_enewick_hybrid_nodes_are_merged() {
+        let text = "((A,(B)#H1:0.5::0.3),(#H1:0.2::0.7,C));";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let (nexus, networks) = parser.parse_enewick().unwrap();
+
+        assert_eq!(nexus.taxa.labels(), &["A", "B", "C"]);
+        assert_eq!(networks.len(), 1);
+
+        let network = &networks[0];
+        assert_eq!(network.name, "network_1");
+        assert_eq!(network.nodes.len(), 7);
+        assert!(!network.is_tree());
+
+        let hybrids: Vec<usize> = network.reticulations().collect();
+        assert_eq!(hybrids.len(), 1);
+        let hybrid = hybrids[0];
+        assert_eq!(
+            network.nodes[hybrid],
+            NetworkNode::HybridNode {
+                hybrid: "H1".to_string(),
+                label: None,
+            }
+        );
+
+        let mut gammas: Vec<Option<f64>> = network
+            .parent_edges(hybrid)
+            .map(|edge| edge.gamma)
+            .collect();
+        gammas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(gammas, vec![Some(0.3), Some(0.7)]);
+
+        let child_edges: Vec<&NetworkEdge> = network.child_edges(hybrid).collect();
+        assert_eq!(child_edges.len(), 1);
+        assert_eq!(
+            network.nodes[child_edges[0].child],
+            NetworkNode::Leaf { taxon: 1 }
+        );
+
+        let root = network.root().unwrap();
+        assert_eq!(network.nodes[root], NetworkNode::Root { label: None });
+    }
+
+    #[test]
+    fn test_enewick_edge_values() {
+        let text = "(A:1:90:0.4,B::95);";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let (_, networks) = parser.parse_enewick().unwrap();
+
+        let network = &networks[0];
+        assert!(network.is_tree());
+        assert_eq!(
+            network.edges,
+            vec![
+                NetworkEdge {
+                    parent: 2,
+                    child: 0,
+                    length: Some(1.0),
+                    support: Some(90.0),
+                    gamma: Some(0.4),
+                },
+                NetworkEdge {
+                    parent: 2,
+                    child: 1,
+                    length: None,
+                    support: Some(95.0),
+                    gamma: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_networks_in_trees_block() {
+        let text = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=3;
+            TAXLABELS A B C;
+        END;
+        BEGIN TREES;
+            TRANSLATE
+                1 A,
+                2 B,
+                3 C;
+            TREE net1 = ((1,#LGT2),(2,(3)#LGT2));
+            TREE net2 = (1,(2,3));
+        END;
+        ";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let (nexus, networks) = parser.parse_networks().unwrap();
+
+        assert_eq!(nexus.taxa.labels(), &["A", "B", "C"]);
+        assert!(matches!(
+            &nexus.blocks[1],
+            NexusBlock::TreesBlock(translations, trees) if translations.len() == 3 && trees.is_empty()
+        ));
+
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks[0].name, "net1");
+        assert_eq!(networks[0].reticulations().count(), 1);
+        assert!(networks[1].is_tree());
+
+        let leaves: Vec<&NetworkNode> = networks[0]
+            .nodes
+            .iter()
+            .filter(|node| matches!(node, NetworkNode::Leaf { .. }))
+            .collect();
+        assert_eq!(
+            leaves,
+            vec![
+                &NetworkNode::Leaf { taxon: 0 },
+                &NetworkNode::Leaf { taxon: 1 },
+                &NetworkNode::Leaf { taxon: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_network() {
+        let text = "((A,#H1),);";
+        let lexer = Lexer::new(text);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+
+        assert_eq!(
+            parser.parse_enewick(),
+            Err(ParsingError::MalformedTreeStructure)
+        );
+    }
+}
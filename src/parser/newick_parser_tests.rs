@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use indextree::Arena;
+
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::{Parser, ParsingError},
+        types::{
+            nexus::NexusBlock,
+            tree::{Tree, TreeNode},
+        },
+    };
+
+    #[test]
+    fn test_multi_newick() {
+        let text = "((Apes:1,Humans:2)95:0.5,Gorillas:3);
+        (Gorillas,(Humans,'Pan troglodytes'));
+        ";
+        let lexer = Lexer::new(text);
+        let tokens = Tokens::new(&lexer);
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_newick().unwrap();
+
+        assert_eq!(
+            result.taxa.labels(),
+            &["Apes", "Humans", "Gorillas", "Pan troglodytes"]
+        );
+
+        let mut t1 = Arena::new();
+        let apes = t1.new_node(TreeNode::new_leaf(0).with_length(1.0));
+        let humans = t1.new_node(TreeNode::new_leaf(1).with_length(2.0));
+        let apes_humans = t1.new_node(
+            TreeNode::new_internal()
+                .with_label("95".to_string())
+                .with_length(0.5),
+        );
+        let gorillas = t1.new_node(TreeNode::new_leaf(2).with_length(3.0));
+        let root = t1.new_node(TreeNode::new_root());
+        apes_humans.append(apes, &mut t1);
+        apes_humans.append(humans, &mut t1);
+        root.append(apes_humans, &mut t1);
+        root.append(gorillas, &mut t1);
+
+        let mut t2 = Arena::new();
+        let gorillas = t2.new_node(TreeNode::new_leaf(2));
+        let humans = t2.new_node(TreeNode::new_leaf(1));
+        let pan = t2.new_node(TreeNode::new_leaf(3));
+        let humans_pan = t2.new_node(TreeNode::new_internal());
+        let root = t2.new_node(TreeNode::new_root());
+        humans_pan.append(humans, &mut t2);
+        humans_pan.append(pan, &mut t2);
+        root.append(gorillas, &mut t2);
+        root.append(humans_pan, &mut t2);
+
+        assert_eq!(
+            result.blocks,
+            vec![NexusBlock::TreesBlock(
+                Default::default(),
+                vec![
+                    Tree {
+                        tree: t1,
+                        name: "tree_1".to_string(),
+                        rooted: false
+                    },
+                    Tree {
+                        tree: t2,
+                        name: "tree_2".to_string(),
+                        rooted: false
+                    }
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unrooted_newick_with_polytomy() {
+        let text = "(A:0.1,B:0.2,(C:0.3,D:0.4)100:0.5);";
+        let lexer = Lexer::new(text);
+        let tokens = Tokens::new(&lexer);
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_newick().unwrap();
+
+        let Some(NexusBlock::TreesBlock(_, trees)) = result.blocks.first() else {
+            panic!("Expected a trees block.");
+        };
+        let root = trees[0]
+            .tree
+            .iter()
+            .find(|node| node.parent().is_none())
+            .unwrap();
+
+        assert_eq!(root.get(), &TreeNode::new_root());
+        assert_eq!(
+            trees[0]
+                .tree
+                .get_node_id(root)
+                .unwrap()
+                .children(&trees[0].tree)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_empty_newick() {
+        let lexer = Lexer::new("  \n");
+        let tokens = Tokens::new(&lexer);
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_newick().unwrap();
+
+        assert_eq!(
+            result.blocks,
+            vec![NexusBlock::TreesBlock(Default::default(), vec![])]
+        );
+    }
+
+    #[test]
+    fn test_newick_without_semicolon() {
+        let lexer = Lexer::new("(A,B);\n(A,C)");
+        let tokens = Tokens::new(&lexer);
+        let mut parser = Parser::new(tokens);
+
+        assert_eq!(parser.parse_newick(), Err(ParsingError::MissingEOS));
+    }
+}
@@ -1,11 +1,18 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, fmt::Display};
 
+use fnv::FnvHashMap;
 use indextree::{Arena, NodeId};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::{
     lexer::tokens::{Token, Tokens},
+    parser::annotation::parse_attributes,
     types::{
+        characters::{CharacterMatrix, DataType, character_count},
+        network::{Network, NetworkEdge, NetworkNode},
         nexus::{Nexus, NexusBlock},
+        taxa::{Taxa, TaxonId},
         tree::{Tree, TreeNode},
     },
 };
@@ -20,27 +27,141 @@ pub enum ParsingError {
     UnexpectedToken(String),
     InvalidNumber,
     UnexpectedFileEnd,
+    /// Input which is no token, see [`LexingError`](crate::LexingError).
+    InvalidToken,
     // taxa block
     InvalidList,
     TaxaDimensionsMismatch,
     // trees block
     DuplicateTranslations,
     TranslationForUnknownTaxa,
+    // characters block
+    CharactersDimensionsMismatch,
+    DuplicateTaxa,
     // tree parsing
     MalformedTreeStructure,
+    MalformedAnnotation,
     DuplicateTreeNames,
 }
 
+impl Display for ParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParsingError::MissingNexusTag => write!(f, "The file does not start with #NEXUS"),
+            ParsingError::MissingEOS => write!(f, "Expected ';'"),
+            ParsingError::InvalidBlock => write!(f, "Unknown block type"),
+            ParsingError::MissingToken(token) => write!(f, "Expected '{}'", token),
+            ParsingError::UnexpectedToken(token) => write!(f, "Unexpected token ({})", token),
+            ParsingError::InvalidNumber => write!(f, "Expected a number"),
+            ParsingError::UnexpectedFileEnd => write!(f, "Unexpected end of file"),
+            ParsingError::InvalidToken => {
+                write!(f, "Invalid token, such as an unterminated quote or comment")
+            }
+            ParsingError::InvalidList => write!(f, "Invalid list of labels"),
+            ParsingError::TaxaDimensionsMismatch => {
+                write!(f, "The number of taxa does not match the dimensions")
+            }
+            ParsingError::DuplicateTranslations => {
+                write!(f, "A key or taxon occurs twice in the translate table")
+            }
+            ParsingError::TranslationForUnknownTaxa => {
+                write!(
+                    f,
+                    "The translate table refers to a taxon which is not defined"
+                )
+            }
+            ParsingError::CharactersDimensionsMismatch => {
+                write!(
+                    f,
+                    "The number of sequences or characters does not match the dimensions"
+                )
+            }
+            ParsingError::DuplicateTaxa => write!(f, "A taxon occurs twice in the matrix"),
+            ParsingError::MalformedTreeStructure => write!(f, "Malformed tree"),
+            ParsingError::MalformedAnnotation => write!(f, "Malformed [&...] annotation"),
+            ParsingError::DuplicateTreeNames => write!(f, "Two trees have the same name"),
+        }
+    }
+}
+
+/// Maps the keys of a translate table to the taxa they stand for. Keys borrow from
+/// the parsed source unless the table outlives it. Every leaf is looked up, so the
+/// short keys are hashed with FNV rather than the slower default hasher.
+pub(crate) type TranslatedTaxa<'a> = FnvHashMap<Cow<'a, str>, TaxonId>;
+
 pub struct Parser<'a> {
     tokens: Tokens<'a>,
+    taxa: Taxa,
+    translated_taxa: Cow<'a, TranslatedTaxa<'a>>,
+    error_position: Option<usize>,
+    child_ids: Vec<NodeId>,
+    #[cfg(feature = "parallel")]
+    parallel_trees: bool,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(tokens: Tokens<'a>) -> Self {
-        Self { tokens }
+        Self {
+            tokens,
+            taxa: Taxa::new(),
+            translated_taxa: Cow::Owned(TranslatedTaxa::default()),
+            error_position: None,
+            child_ids: vec![],
+            #[cfg(feature = "parallel")]
+            parallel_trees: false,
+        }
+    }
+
+    /// Creates a parser which resolves leaves using already known taxa and translations.
+    pub(crate) fn with_taxa(
+        tokens: Tokens<'a>,
+        taxa: Taxa,
+        translated_taxa: &'a TranslatedTaxa<'a>,
+    ) -> Self {
+        Self {
+            tokens,
+            taxa,
+            translated_taxa: Cow::Borrowed(translated_taxa),
+            error_position: None,
+            child_ids: vec![],
+            #[cfg(feature = "parallel")]
+            parallel_trees: false,
+        }
+    }
+
+    pub(crate) fn into_taxa(self) -> Taxa {
+        self.taxa
+    }
+
+    pub(crate) fn translated_taxa(&self) -> &TranslatedTaxa<'a> {
+        &self.translated_taxa
+    }
+
+    /// Parses the TREE statements of a trees block in parallel once its header and
+    /// translate table are known. The trees and errors are the same as when parsing
+    /// sequentially.
+    #[cfg(feature = "parallel")]
+    pub fn with_parallel_trees(mut self, parallel_trees: bool) -> Self {
+        self.parallel_trees = parallel_trees;
+        self
+    }
+
+    /// The byte offset the parser stopped at.
+    pub fn position(&self) -> usize {
+        self.tokens.cursor()
+    }
+
+    /// The byte offset of the token the last parsing error occurred at, e.g. to
+    /// locate the error returned by [`Parser::parse`].
+    pub fn error_position(&self) -> Option<usize> {
+        self.error_position
     }
 
     pub fn parse(&mut self) -> Result<Nexus, ParsingError> {
+        self.parse_input(Self::parse_blocks)
+    }
+
+    fn parse_blocks(&mut self) -> Result<Nexus, ParsingError> {
         self.parse_nexus_tag()?;
 
         let mut blocks: Vec<NexusBlock> = vec![];
@@ -48,7 +169,55 @@ impl<'a> Parser<'a> {
             blocks.push(block);
         }
 
-        Nexus::build(blocks)
+        Nexus::build(blocks, std::mem::take(&mut self.taxa)).map_err(|error| self.error(error))
+    }
+
+    /// Parses all blocks up to the first TREE statement of the first trees block,
+    /// which is left as the next statement to parse. The returned Nexus contains the
+    /// trees block without trees, so that its translations are validated as usual.
+    pub(crate) fn parse_trees_header(&mut self) -> Result<Nexus, ParsingError> {
+        self.parse_input(Self::parse_header)
+    }
+
+    fn parse_header(&mut self) -> Result<Nexus, ParsingError> {
+        self.parse_nexus_tag()?;
+
+        let mut blocks: Vec<NexusBlock> = vec![];
+        loop {
+            self.parse_and_ignore_whitespace();
+
+            if self.tokens.peek().is_none() {
+                break;
+            }
+
+            self.parse_keyword("begin")?;
+
+            if self.try_parser(|s| s.parse_keyword("taxa")).is_ok() {
+                blocks.extend(self.parse_taxa_block()?);
+                continue;
+            }
+            if self
+                .try_parser(|s| s.parse_keyword("characters"))
+                .or_else(|_| self.try_parser(|s| s.parse_keyword("data")))
+                .is_ok()
+            {
+                blocks.extend(self.parse_characters_block()?);
+                continue;
+            }
+            if self.try_parser(|s| s.parse_keyword("trees")).is_ok() {
+                self.parse_eos()?;
+                let translations = self.parse_taxa_translations()?;
+                blocks.push(
+                    NexusBlock::build_trees_block(translations, vec![])
+                        .map_err(|error| self.error(error))?,
+                );
+                break;
+            }
+
+            return Err(self.error(ParsingError::InvalidBlock));
+        }
+
+        Nexus::build(blocks, self.taxa.clone()).map_err(|error| self.error(error))
     }
 
     fn parse_nexus_tag(&mut self) -> Result<&str, ParsingError> {
@@ -71,8 +240,15 @@ impl<'a> Parser<'a> {
         if self.try_parser(|s| s.parse_keyword("trees")).is_ok() {
             return self.parse_trees_block();
         }
+        if self
+            .try_parser(|s| s.parse_keyword("characters"))
+            .or_else(|_| self.try_parser(|s| s.parse_keyword("data")))
+            .is_ok()
+        {
+            return self.parse_characters_block();
+        }
 
-        Err(ParsingError::InvalidBlock)
+        Err(self.error(ParsingError::InvalidBlock))
     }
 
     // taxa block parsing
@@ -87,26 +263,33 @@ impl<'a> Parser<'a> {
         self.parse_eos()?;
 
         self.parse_keyword("TaxLabels")?;
-        let taxa_labels = self
+        let taxa_labels: Vec<String> = self
             .parse_words()?
             .into_iter()
             .map(|s| s.to_string())
             .collect();
         self.parse_eos()?;
 
+        for label in &taxa_labels {
+            self.taxa.get_or_insert(label);
+        }
+
         self.parse_keyword("end")?;
         self.parse_eos()?;
 
-        Ok(Some(NexusBlock::build_taxa_block(dimension, taxa_labels)?))
+        NexusBlock::build_taxa_block(dimension, taxa_labels)
+            .map(Some)
+            .map_err(|error| self.error(error))
     }
 
     fn parse_words(&mut self) -> Result<Vec<&'a str>, ParsingError> {
         let mut labels = vec![];
 
         while self.tokens.peek() != Some(&Token::EOS) {
+            let start = self.tokens.cursor();
             match self.parse_word() {
                 Ok(word) => labels.push(word),
-                _ => return Err(ParsingError::InvalidList),
+                _ => return Err(self.error_at(start, ParsingError::InvalidList)),
             }
             self.parse_and_ignore_whitespace();
         }
@@ -114,6 +297,128 @@ impl<'a> Parser<'a> {
         Ok(labels)
     }
 
+    // characters block parsing
+
+    fn parse_characters_block(&mut self) -> Result<Option<NexusBlock>, ParsingError> {
+        self.parse_eos()?;
+
+        self.parse_keyword("Dimensions")?;
+        let mut ntax = None;
+        let mut nchar = None;
+        for (key, value) in self.parse_assignments()? {
+            let count = value.and_then(|value| value.parse::<usize>().ok());
+            let mut count = || count.ok_or_else(|| self.error(ParsingError::InvalidNumber));
+            match key.to_ascii_lowercase().as_str() {
+                "ntax" => ntax = Some(count()?),
+                "nchar" => nchar = Some(count()?),
+                _ => {}
+            }
+        }
+        let nchar =
+            nchar.ok_or_else(|| self.error(ParsingError::MissingToken(String::from("nchar"))))?;
+
+        let mut matrix = CharacterMatrix::new(DataType::Standard);
+        let mut interleave = false;
+        if self.try_parser(|s| s.parse_keyword("Format")).is_ok() {
+            for (key, value) in self.parse_assignments()? {
+                match (key.to_ascii_lowercase().as_str(), value) {
+                    ("datatype", Some(value)) => {
+                        matrix.datatype = DataType::from_keyword(value).ok_or_else(|| {
+                            self.error(ParsingError::UnexpectedToken(value.to_string()))
+                        })?
+                    }
+                    ("missing", Some(value)) => {
+                        matrix.missing = value.chars().next().unwrap_or(matrix.missing)
+                    }
+                    ("gap", Some(value)) => matrix.gap = value.chars().next().unwrap_or(matrix.gap),
+                    ("interleave", value) => {
+                        interleave = !value.is_some_and(|value| value.eq_ignore_ascii_case("no"))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self.parse_keyword("Matrix")?;
+        matrix.sequences = self.parse_matrix(nchar, interleave)?;
+        self.parse_eos()?;
+
+        self.parse_keyword("end")?;
+        self.parse_eos()?;
+
+        NexusBlock::build_characters_block(ntax, nchar, matrix)
+            .map(Some)
+            .map_err(|error| self.error(error))
+    }
+
+    /// Parses `key` and `key=value` entries up to and including the end of the
+    /// statement, e.g. the ones of a DIMENSIONS or FORMAT statement.
+    fn parse_assignments(&mut self) -> Result<Vec<(&'a str, Option<&'a str>)>, ParsingError> {
+        let mut assignments = vec![];
+
+        while self.try_parser(|s| s.parse_eos()).is_err() {
+            let key = self.parse_word()?;
+            let value = self
+                .try_parser(|s| {
+                    s.parse_punctuation("=")?;
+                    s.parse_word()
+                })
+                .ok();
+            assignments.push((key, value));
+        }
+
+        Ok(assignments)
+    }
+
+    /// Parses the rows of a matrix, each being a taxon followed by its sequence. In
+    /// sequential matrices a sequence may be wrapped over several lines and ends
+    /// after `nchar` characters, while the rows of interleaved matrices end with
+    /// their line and are appended to the sequence of their taxon.
+    fn parse_matrix(
+        &mut self,
+        nchar: usize,
+        interleave: bool,
+    ) -> Result<Vec<(TaxonId, String)>, ParsingError> {
+        let mut sequences: Vec<(TaxonId, String)> = vec![];
+
+        loop {
+            self.parse_and_ignore_whitespace();
+            if matches!(self.tokens.peek(), Some(Token::EOS) | None) {
+                return Ok(sequences);
+            }
+
+            let taxon = self.parse_word()?;
+            let taxon = self.taxa.get_or_insert(taxon);
+
+            let mut sequence = String::new();
+            loop {
+                match self.tokens.peek() {
+                    Some(Token::EOS) | None => break,
+                    Some(Token::Whitespace(whitespace))
+                        if interleave && whitespace.contains('\n') =>
+                    {
+                        break;
+                    }
+                    _ => {}
+                }
+                let start = self.tokens.cursor();
+                self.tokens.next();
+                let states = self.tokens.slice_from_to(start, self.tokens.cursor());
+                sequence.extend(states.chars().filter(|state| !state.is_whitespace()));
+                // a polymorphism counts as one character, so there are at least as
+                // many states as characters
+                if !interleave && sequence.len() >= nchar && character_count(&sequence) >= nchar {
+                    break;
+                }
+            }
+
+            match sequences.iter_mut().find(|(id, _)| *id == taxon) {
+                Some((_, row)) => row.push_str(&sequence),
+                None => sequences.push((taxon, sequence)),
+            }
+        }
+    }
+
     // trees block parsing
 
     fn parse_trees_block(&mut self) -> Result<Option<NexusBlock>, ParsingError> {
@@ -125,10 +430,14 @@ impl<'a> Parser<'a> {
         self.parse_keyword("end")?;
         self.parse_eos()?;
 
-        Ok(Some(NexusBlock::build_trees_block(translations, trees)?))
+        NexusBlock::build_trees_block(translations, trees)
+            .map(Some)
+            .map_err(|error| self.error(error))
     }
 
-    fn parse_taxa_translations(&mut self) -> Result<HashMap<String, String>, ParsingError> {
+    fn parse_taxa_translations(&mut self) -> Result<HashMap<String, TaxonId>, ParsingError> {
+        self.translated_taxa = Cow::Owned(TranslatedTaxa::default());
+
         if self.try_parser(|s| s.parse_keyword("Translate")).is_err() {
             return Ok(HashMap::new());
         }
@@ -156,15 +465,15 @@ impl<'a> Parser<'a> {
                         s.parse_eos()?;
                         Ok(taxa_name)
                     }) {
+                        let taxon =
+                            self.add_translated_taxon(translated_taxa_name, actual_taxa_name);
                         if translations
-                            .insert(
-                                translated_taxa_name.to_string(),
-                                actual_taxa_name.to_string(),
-                            )
+                            .insert(translated_taxa_name.to_string(), taxon)
                             .is_some()
                         {
                             // there is already a translation with this key
-                            return Err(ParsingError::DuplicateTranslations);
+                            return Err(self
+                                .error_at(translation_start, ParsingError::DuplicateTranslations));
                         }
                         return Ok(translations);
                     }
@@ -176,49 +485,162 @@ impl<'a> Parser<'a> {
                         s.parse_punctuation(",")?;
                         Ok(taxa_name)
                     }) {
-                        translations.insert(
-                            translated_taxa_name.to_string(),
-                            actual_taxa_name.to_string(),
-                        );
+                        let taxon =
+                            self.add_translated_taxon(translated_taxa_name, actual_taxa_name);
+                        translations.insert(translated_taxa_name.to_string(), taxon);
                         translation_start = self.tokens.cursor();
                     }
                 }
-                None => return Err(ParsingError::UnexpectedFileEnd),
+                None => return Err(self.error(ParsingError::UnexpectedFileEnd)),
                 _ => translation_end = self.tokens.cursor(),
             };
         }
     }
 
+    fn add_translated_taxon(
+        &mut self,
+        translated_taxa_name: &'a str,
+        actual_taxa_name: &str,
+    ) -> TaxonId {
+        let taxon = self.taxa.get_or_insert(actual_taxa_name);
+        self.translated_taxa
+            .to_mut()
+            .insert(Cow::Borrowed(translated_taxa_name), taxon);
+        taxon
+    }
+
+    fn resolve_taxon(&mut self, taxon_name: &str) -> TaxonId {
+        match self.translated_taxa.get(taxon_name) {
+            Some(taxon) => *taxon,
+            None => self.taxa.get_or_insert(taxon_name),
+        }
+    }
+
     fn parse_trees(&mut self) -> Result<Vec<Tree>, ParsingError> {
+        #[cfg(feature = "parallel")]
+        if self.parallel_trees {
+            return self.parse_trees_in_parallel();
+        }
+
         let mut trees = vec![];
 
-        while let Ok(tree) = self.try_parser(|s| s.parse_tree()) {
+        while self.peek_keyword("TREE") {
+            trees.push(self.parse_tree_statement()?);
+        }
+
+        Ok(trees)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn parse_trees_in_parallel(&mut self) -> Result<Vec<Tree>, ParsingError> {
+        let statements = self.find_tree_statements();
+        let known_taxa = self.taxa.len();
+
+        // the trees with the labels of their new taxa, or the errors with their position
+        type TreeResult = Result<(Tree, Vec<String>), (ParsingError, Option<usize>)>;
+        // the parsers of the trees borrow the translations instead of copying them
+        let translated_taxa: &TranslatedTaxa = &self.translated_taxa;
+        let results: Vec<TreeResult> = statements
+            .par_iter()
+            .map_init(
+                || Parser::with_taxa(self.tokens.clone(), self.taxa.clone(), translated_taxa),
+                |parser, (start, _)| {
+                    parser.tokens.set_cursor(*start);
+                    let tree = parser
+                        .parse_tree_statement()
+                        .map_err(|error| (error, parser.error_position))?;
+
+                    // taxa first seen in this tree only have an id local to this parser
+                    let new_taxa = tree
+                        .tree
+                        .iter()
+                        .filter_map(|node| match node.get() {
+                            TreeNode::Leaf { taxon, .. } if *taxon >= known_taxa => {
+                                parser.taxa.label(*taxon).map(|label| label.to_string())
+                            }
+                            _ => None,
+                        })
+                        .collect();
+
+                    Ok((tree, new_taxa))
+                },
+            )
+            .collect();
+
+        let mut trees = Vec::with_capacity(results.len());
+
+        for ((_, end), result) in statements.into_iter().zip(results) {
+            let (mut tree, new_taxa) = result.map_err(|(error, position)| {
+                self.error_position = position;
+                error
+            })?;
+
+            let mut new_taxa = new_taxa.iter();
+            for node in tree.tree.iter_mut() {
+                if let TreeNode::Leaf { taxon, .. } = node.get_mut()
+                    && *taxon >= known_taxa
+                {
+                    *taxon = self.taxa.get_or_insert(new_taxa.next().unwrap());
+                }
+            }
+
             trees.push(tree);
+            self.tokens.set_cursor(end);
         }
 
         Ok(trees)
     }
 
-    fn parse_tree(&mut self) -> Result<Tree, ParsingError> {
+    /// Returns the token ranges of the consecutive TREE statements starting at the
+    /// cursor, without moving the cursor.
+    #[cfg(feature = "parallel")]
+    fn find_tree_statements(&mut self) -> Vec<(usize, usize)> {
+        let initial_cursor = self.tokens.cursor();
+
+        let mut statements = vec![];
+        let mut statement_start = initial_cursor;
+
+        while self.parse_keyword("TREE").is_ok() {
+            while !matches!(self.tokens.next(), Some(Token::EOS) | None) {}
+            statements.push((statement_start, self.tokens.cursor()));
+            statement_start = self.tokens.cursor();
+        }
+
+        self.tokens.set_cursor(initial_cursor);
+        statements
+    }
+
+    pub(crate) fn parse_tree(&mut self) -> Result<Tree, ParsingError> {
+        self.parse_input(Self::parse_tree_statement)
+    }
+
+    fn parse_tree_statement(&mut self) -> Result<Tree, ParsingError> {
         self.parse_keyword("TREE")?;
         let tree_name = self.parse_word()?;
+        // annotations of the tree itself, such as BEAST's [&lnP=-1234.5], are skipped
+        self.parse_annotations();
         self.parse_punctuation("=")?;
-        let tree = self.parse_nexus()?;
+        let (tree, rooted) = self.parse_nexus()?;
         self.parse_eos()?;
         Ok(Tree {
             tree,
             name: tree_name.to_string(),
-            rooted: false,
+            rooted,
         })
     }
 
-    fn parse_nexus(&mut self) -> Result<Arena<TreeNode>, ParsingError> {
+    /// Parses a tree and whether it is marked as rooted by a leading `[&R]`.
+    fn parse_nexus(&mut self) -> Result<(Arena<TreeNode>, bool), ParsingError> {
         self.parse_and_ignore_whitespace();
+        let rooted = self
+            .parse_annotations()
+            .iter()
+            .any(|annotation| annotation.trim().eq_ignore_ascii_case("R"));
 
         let mut arena = Arena::new();
         self.parse_nexus_subtree(&mut arena, true)?;
 
-        Ok(arena)
+        Ok((arena, rooted))
     }
 
     fn parse_nexus_subtree(
@@ -226,50 +648,298 @@ impl<'a> Parser<'a> {
         arena: &mut Arena<TreeNode>,
         is_root: bool,
     ) -> Result<NodeId, ParsingError> {
-        if self.try_parser(|s| s.parse_punctuation("(")).is_ok() {
-            let child_id_1 = self.parse_nexus_subtree(arena, false)?;
-            self.parse_punctuation(",")?;
-
-            let child_id_2 = self.parse_nexus_subtree(arena, false)?;
+        if self.peek_punctuation("(") {
+            self.parse_punctuation("(")?;
+            // the children of all open subtrees are kept on one stack
+            let first_child = self.child_ids.len();
+            let child_id = self.parse_nexus_subtree(arena, false)?;
+            self.child_ids.push(child_id);
+            while self.peek_punctuation(",") {
+                self.parse_punctuation(",")?;
+                let child_id = self.parse_nexus_subtree(arena, false)?;
+                self.child_ids.push(child_id);
+            }
             self.parse_punctuation(")")?;
 
-            let subtree_root_node = match is_root {
+            let mut subtree_root_node = match is_root {
                 true => TreeNode::new_root(),
                 false => TreeNode::new_internal(),
             };
-
-            let subtree_root_node = match self.try_parser(|s| {
-                s.parse_punctuation(":")?;
-                s.parse_f64()
-            }) {
-                Ok(length) => subtree_root_node.with_length(length),
-                _ => subtree_root_node,
-            };
+            if self.peek_word() {
+                subtree_root_node = subtree_root_node.with_label(self.parse_word()?.to_string());
+            }
+            self.parse_node_annotations(&mut subtree_root_node)?;
+            if let Some(length) = self.parse_branch_length() {
+                subtree_root_node = subtree_root_node.with_length(length);
+            }
+            self.parse_node_annotations(&mut subtree_root_node)?;
 
             let subtree_root_node_id = arena.new_node(subtree_root_node);
-            subtree_root_node_id.append(child_id_1, arena);
-            subtree_root_node_id.append(child_id_2, arena);
+            for child_id in self.child_ids.drain(first_child..) {
+                subtree_root_node_id.append(child_id, arena);
+            }
 
             return Ok(subtree_root_node_id);
         }
 
-        if let Ok(taxon) = self.try_parser(|s| s.parse_word()) {
-            let leaf = TreeNode::new_leaf(taxon.to_string());
-
-            let leaf = match self.try_parser(|s| {
-                s.parse_punctuation(":")?;
-                s.parse_f64()
-            }) {
-                Ok(length) => leaf.with_length(length),
-                _ => leaf,
-            };
+        if let Ok(taxon_name) = self.try_parser(|s| s.parse_word()) {
+            let mut leaf = TreeNode::new_leaf(self.resolve_taxon(taxon_name));
+            self.parse_node_annotations(&mut leaf)?;
+            if let Some(length) = self.parse_branch_length() {
+                leaf = leaf.with_length(length);
+            }
+            self.parse_node_annotations(&mut leaf)?;
 
             let leaf_node_id = arena.new_node(leaf);
 
             return Ok(leaf_node_id);
         }
 
-        Err(ParsingError::MalformedTreeStructure)
+        self.parse_and_ignore_whitespace();
+        Err(self.error(ParsingError::MalformedTreeStructure))
+    }
+
+    /// Parses the optional `:length` following a node.
+    fn parse_branch_length(&mut self) -> Option<f64> {
+        if !self.peek_punctuation(":") {
+            return None;
+        }
+
+        self.try_parser(|s| {
+            s.parse_punctuation(":")?;
+            s.parse_f64()
+        })
+        .ok()
+    }
+
+    /// Adds the attributes of the annotations following a node, which BEAST writes
+    /// before and MrBayes after the branch length.
+    fn parse_node_annotations(&mut self, node: &mut TreeNode) -> Result<(), ParsingError> {
+        self.parse_and_ignore_whitespace();
+
+        while let Some(Token::Annotation(annotation)) = self.tokens.peek() {
+            let annotation = *annotation;
+            let start = self.tokens.cursor();
+            self.tokens.next();
+
+            let attributes =
+                parse_attributes(annotation).map_err(|error| self.error_at(start, error))?;
+            node.attributes_mut().extend(attributes);
+            self.parse_and_ignore_whitespace();
+        }
+        Ok(())
+    }
+
+    // newick parsing
+
+    /// Parses a plain Newick file with one or more trees, each terminated by a
+    /// semicolon. The trees are returned in a single trees block and are named
+    /// `tree_1`, `tree_2`, etc.
+    pub fn parse_newick(&mut self) -> Result<Nexus, ParsingError> {
+        self.parse_input(Self::parse_newick_trees)
+    }
+
+    fn parse_newick_trees(&mut self) -> Result<Nexus, ParsingError> {
+        let mut trees = vec![];
+
+        self.parse_and_ignore_whitespace();
+        while self.tokens.peek().is_some() {
+            let (tree, rooted) = self.parse_nexus()?;
+            self.parse_eos()?;
+            self.parse_and_ignore_whitespace();
+
+            trees.push(Tree {
+                tree,
+                name: format!("tree_{}", trees.len() + 1),
+                rooted,
+            });
+        }
+
+        let trees_block = NexusBlock::build_trees_block(HashMap::new(), trees)
+            .map_err(|error| self.error(error))?;
+        Nexus::build(vec![trees_block], std::mem::take(&mut self.taxa))
+            .map_err(|error| self.error(error))
+    }
+
+    // network parsing
+
+    /// Parses a NEXUS file whose TREE statements are extended Newick networks. The
+    /// returned Nexus contains the trees blocks with their translations but without
+    /// trees; the networks of all trees blocks are returned in order.
+    pub fn parse_networks(&mut self) -> Result<(Nexus, Vec<Network>), ParsingError> {
+        self.parse_input(Self::parse_network_blocks)
+    }
+
+    fn parse_network_blocks(&mut self) -> Result<(Nexus, Vec<Network>), ParsingError> {
+        self.parse_nexus_tag()?;
+
+        let mut blocks: Vec<NexusBlock> = vec![];
+        let mut networks = vec![];
+        loop {
+            self.parse_and_ignore_whitespace();
+
+            if self.tokens.peek().is_none() {
+                break;
+            }
+
+            self.parse_keyword("begin")?;
+
+            if self.try_parser(|s| s.parse_keyword("taxa")).is_ok() {
+                blocks.extend(self.parse_taxa_block()?);
+                continue;
+            }
+            if self
+                .try_parser(|s| s.parse_keyword("characters"))
+                .or_else(|_| self.try_parser(|s| s.parse_keyword("data")))
+                .is_ok()
+            {
+                blocks.extend(self.parse_characters_block()?);
+                continue;
+            }
+            if self.try_parser(|s| s.parse_keyword("trees")).is_ok() {
+                self.parse_eos()?;
+                let translations = self.parse_taxa_translations()?;
+                while self.peek_keyword("TREE") {
+                    networks.push(self.parse_network()?);
+                }
+                self.parse_keyword("end")?;
+                self.parse_eos()?;
+
+                blocks.push(
+                    NexusBlock::build_trees_block(translations, vec![])
+                        .map_err(|error| self.error(error))?,
+                );
+                continue;
+            }
+
+            return Err(self.error(ParsingError::InvalidBlock));
+        }
+
+        let nexus = Nexus::build(blocks, std::mem::take(&mut self.taxa))
+            .map_err(|error| self.error(error))?;
+        Ok((nexus, networks))
+    }
+
+    /// Parses one or more extended Newick networks, each terminated by a semicolon.
+    /// The networks are named `network_1`, `network_2`, etc.
+    pub fn parse_enewick(&mut self) -> Result<(Nexus, Vec<Network>), ParsingError> {
+        self.parse_input(Self::parse_enewick_networks)
+    }
+
+    fn parse_enewick_networks(&mut self) -> Result<(Nexus, Vec<Network>), ParsingError> {
+        let mut networks = vec![];
+
+        self.parse_and_ignore_whitespace();
+        while self.tokens.peek().is_some() {
+            let network = self.parse_enewick_network(format!("network_{}", networks.len() + 1))?;
+            self.parse_eos()?;
+            self.parse_and_ignore_whitespace();
+
+            networks.push(network);
+        }
+
+        let nexus = Nexus::build(vec![], std::mem::take(&mut self.taxa))
+            .map_err(|error| self.error(error))?;
+        Ok((nexus, networks))
+    }
+
+    fn parse_network(&mut self) -> Result<Network, ParsingError> {
+        self.parse_keyword("TREE")?;
+        let network_name = self.parse_word()?;
+        self.parse_punctuation("=")?;
+        let network = self.parse_enewick_network(network_name.to_string())?;
+        self.parse_eos()?;
+        Ok(network)
+    }
+
+    fn parse_enewick_network(&mut self, name: String) -> Result<Network, ParsingError> {
+        self.parse_and_ignore_whitespace();
+
+        let mut network = Network::new(name);
+        let mut hybrid_nodes = HashMap::new();
+        self.parse_network_subtree(&mut network, &mut hybrid_nodes, true)?;
+
+        Ok(network)
+    }
+
+    /// Parses a subtree and returns its root together with the values of the edge
+    /// above it. Hybrid nodes which were seen before are reused, so that every
+    /// occurrence adds a parent to the same node.
+    fn parse_network_subtree(
+        &mut self,
+        network: &mut Network,
+        hybrid_nodes: &mut HashMap<&'a str, usize>,
+        is_root: bool,
+    ) -> Result<(usize, NetworkEdge), ParsingError> {
+        let mut children = vec![];
+        if self.try_parser(|s| s.parse_punctuation("(")).is_ok() {
+            children.push(self.parse_network_subtree(network, hybrid_nodes, false)?);
+            while self.try_parser(|s| s.parse_punctuation(",")).is_ok() {
+                children.push(self.parse_network_subtree(network, hybrid_nodes, false)?);
+            }
+            self.parse_punctuation(")")?;
+        }
+
+        let label = self.try_parser(|s| s.parse_word()).ok();
+
+        let node = match (label.and_then(|label| label.rsplit_once('#')), label) {
+            (Some((label, hybrid)), _) => match hybrid_nodes.get(hybrid) {
+                Some(node) => *node,
+                None => {
+                    let node = network.add_node(NetworkNode::HybridNode {
+                        hybrid: hybrid.to_string(),
+                        label: (!label.is_empty()).then(|| label.to_string()),
+                    });
+                    hybrid_nodes.insert(hybrid, node);
+                    node
+                }
+            },
+            (None, Some(taxon_name)) if children.is_empty() => {
+                let taxon = self.resolve_taxon(taxon_name);
+                network.add_node(NetworkNode::Leaf { taxon })
+            }
+            (None, None) if children.is_empty() => {
+                self.parse_and_ignore_whitespace();
+                return Err(self.error(ParsingError::MalformedTreeStructure));
+            }
+            (None, label) => {
+                let label = label.map(|label| label.to_string());
+                network.add_node(match is_root {
+                    true => NetworkNode::Root { label },
+                    false => NetworkNode::InternalNode { label },
+                })
+            }
+        };
+
+        for (child, edge) in children {
+            network.edges.push(NetworkEdge {
+                parent: node,
+                child,
+                ..edge
+            });
+        }
+
+        Ok((node, self.parse_network_edge()))
+    }
+
+    /// Parses the optional `:length:support:gamma` suffix of a node, where every
+    /// value may be left empty (e.g. `#H1:0.5::0.3`).
+    fn parse_network_edge(&mut self) -> NetworkEdge {
+        let mut values = [None; 3];
+        for value in &mut values {
+            if self.try_parser(|s| s.parse_punctuation(":")).is_err() {
+                break;
+            }
+            *value = self.try_parser(|s| s.parse_f64()).ok();
+        }
+
+        let [length, support, gamma] = values;
+        NetworkEdge {
+            length,
+            support,
+            gamma,
+            ..Default::default()
+        }
     }
 
     // atomic parsers
@@ -277,86 +947,163 @@ impl<'a> Parser<'a> {
     fn parse_eos(&mut self) -> Result<(), ParsingError> {
         self.parse_and_ignore_whitespace();
 
+        let start = self.tokens.cursor();
         match self.tokens.next() {
             Some(Token::EOS) => Ok(()),
-            _ => Err(ParsingError::MissingEOS),
+            _ => Err(self.error_at(start, ParsingError::MissingEOS)),
         }
     }
 
     fn parse_punctuation(&mut self, expected_punctuation: &str) -> Result<&'a str, ParsingError> {
         self.parse_and_ignore_whitespace();
 
+        let start = self.tokens.cursor();
         match self.tokens.next() {
-            Some(Token::Punctuation(punct)) if punct == &expected_punctuation => {
+            Some(Token::Punctuation(punct)) if punct == expected_punctuation => {
                 self.parse_and_ignore_whitespace();
                 Ok(punct)
             }
-            _ => Err(ParsingError::MissingToken(String::from(
-                expected_punctuation,
-            ))),
+            _ => Err(self.error_at(
+                start,
+                ParsingError::MissingToken(String::from(expected_punctuation)),
+            )),
         }
     }
 
     fn parse_uint(&mut self) -> Result<usize, ParsingError> {
         self.parse_and_ignore_whitespace();
 
+        let start = self.tokens.cursor();
         if let Some(Token::Integer(number)) = self.tokens.next() {
-            Ok(*number as usize)
+            Ok(number as usize)
         } else {
-            Err(ParsingError::InvalidNumber)
+            Err(self.error_at(start, ParsingError::InvalidNumber))
         }
     }
 
     fn parse_f64(&mut self) -> Result<f64, ParsingError> {
         self.parse_and_ignore_whitespace();
 
+        let start = self.tokens.cursor();
         match self.tokens.next() {
-            Some(Token::Integer(number)) => Ok(f64::from(*number)),
-            Some(Token::Float(number)) => Ok(*number),
-            _ => Err(ParsingError::InvalidNumber),
+            Some(Token::Integer(number)) => Ok(f64::from(number)),
+            Some(Token::Float(number)) => Ok(number),
+            _ => Err(self.error_at(start, ParsingError::InvalidNumber)),
         }
     }
 
     fn parse_keyword(&mut self, expected_word: &str) -> Result<&'a str, ParsingError> {
         self.parse_and_ignore_whitespace();
 
+        let start = self.tokens.cursor();
         match self.tokens.next() {
             Some(Token::Word(word)) if word.eq_ignore_ascii_case(expected_word) => {
                 self.parse_and_ignore_whitespace();
                 Ok(word)
             }
-            _ => Err(ParsingError::MissingToken(String::from(expected_word))),
+            _ => Err(self.error_at(
+                start,
+                ParsingError::MissingToken(String::from(expected_word)),
+            )),
         }
     }
 
     fn parse_word(&mut self) -> Result<&'a str, ParsingError> {
         self.parse_and_ignore_whitespace();
 
+        let start = self.tokens.cursor();
         match self.tokens.next() {
             Some(Token::Word(word)) => Ok(word),
             Some(Token::QuotedWord(word)) => Ok(word),
             Some(Token::Float(_)) | Some(Token::Integer(_)) => Ok(self.tokens.last_slice()),
-            Some(token) => Err(ParsingError::UnexpectedToken(token.to_string())),
-            None => Err(ParsingError::UnexpectedFileEnd),
+            Some(token) => {
+                Err(self.error_at(start, ParsingError::UnexpectedToken(token.to_string())))
+            }
+            None => Err(self.error_at(start, ParsingError::UnexpectedFileEnd)),
         }
     }
 
+    /// Parses the consecutive `[&...]` annotations at the cursor, if any.
+    fn parse_annotations(&mut self) -> Vec<&'a str> {
+        let mut annotations = vec![];
+
+        self.parse_and_ignore_whitespace();
+        while let Some(Token::Annotation(annotation)) = self.tokens.peek() {
+            annotations.push(*annotation);
+            self.tokens.next();
+            self.parse_and_ignore_whitespace();
+        }
+
+        annotations
+    }
+
+    /// Whether the next token is the given punctuation. Only whitespace is consumed,
+    /// which is cheaper than a failing parser in the hot paths of tree parsing.
+    fn peek_punctuation(&mut self, expected_punctuation: &str) -> bool {
+        self.parse_and_ignore_whitespace();
+        matches!(self.tokens.peek(), Some(Token::Punctuation(punct)) if *punct == expected_punctuation)
+    }
+
+    /// Whether the next token can be parsed as a word. Only whitespace is consumed.
+    fn peek_word(&mut self) -> bool {
+        self.parse_and_ignore_whitespace();
+        matches!(
+            self.tokens.peek(),
+            Some(Token::Word(_) | Token::QuotedWord(_) | Token::Integer(_) | Token::Float(_))
+        )
+    }
+
+    /// Whether the next word is the given keyword. Only whitespace is consumed.
+    fn peek_keyword(&mut self, keyword: &str) -> bool {
+        self.parse_and_ignore_whitespace();
+        matches!(self.tokens.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
     fn parse_and_ignore_whitespace(&mut self) {
         while let Some(Token::Whitespace(_)) = &self.tokens.peek() {
             self.tokens.next();
         }
     }
 
+    /// Runs a parser over the input. Since the tokens end at input which cannot be
+    /// lexed, the parser would take it for the end of the input, so that is the error.
+    fn parse_input<T, F>(&mut self, parser: F) -> Result<T, ParsingError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParsingError>,
+    {
+        let result = parser(self);
+
+        match self.tokens.error() {
+            Some(error) => Err(self.error_at(error.offset, ParsingError::InvalidToken)),
+            None => result,
+        }
+    }
+
+    /// Records the cursor as the position of an error.
+    fn error(&mut self, error: ParsingError) -> ParsingError {
+        self.error_at(self.tokens.cursor(), error)
+    }
+
+    fn error_at(&mut self, position: usize, error: ParsingError) -> ParsingError {
+        self.error_position = Some(position);
+        error
+    }
+
+    /// Runs a parser speculatively: if it fails, both the cursor and the position of
+    /// the last error are restored, so that only errors which are returned are
+    /// reported.
     fn try_parser<T, F>(&mut self, parser: F) -> Result<T, ParsingError>
     where
         F: FnOnce(&mut Self) -> Result<T, ParsingError>,
     {
         let initial_cursor = self.tokens.cursor();
+        let initial_error_position = self.error_position;
 
         match parser(self) {
             Ok(result) => Ok(result),
             Err(error) => {
                 self.tokens.set_cursor(initial_cursor);
+                self.error_position = initial_error_position;
                 Err(error)
             }
         }
@@ -3,7 +3,10 @@ mod tests {
     use crate::{
         lexer::{lexer::Lexer, tokens::Tokens},
         parser::parser::{Parser, ParsingError},
-        types::nexus::{Nexus, NexusBlock},
+        types::{
+            nexus::{Nexus, NexusBlock},
+            taxa::Taxa,
+        },
     };
 
     #[test]
@@ -28,7 +31,8 @@ mod tests {
                         "Gor''illas".to_string(),
                         "Gor''ill''as".to_string()
                     ]
-                )]
+                )],
+                taxa: Taxa::from_iter(["Apes", "Humans", "Gor", "Gor''illas", "Gor''ill''as"]),
             })
         );
     }
@@ -61,7 +65,8 @@ mod tests {
                         "Gor''illas".to_string(),
                         "Gor''ill''as".to_string()
                     ]
-                )]
+                )],
+                taxa: Taxa::from_iter(["Apes", "Humans", "Gor", "Gor''illas", "Gor''ill''as"]),
             })
         );
     }
@@ -150,7 +155,8 @@ mod tests {
         assert_eq!(
             parser.parse(),
             Ok(Nexus {
-                blocks: vec![NexusBlock::TaxaBlock(0, vec![])]
+                blocks: vec![NexusBlock::TaxaBlock(0, vec![])],
+                taxa: Taxa::new(),
             })
         );
     }
@@ -175,7 +181,8 @@ mod tests {
                         "Species#2".to_string(),
                         "Species$3".to_string()
                     ]
-                )]
+                )],
+                taxa: Taxa::from_iter(["Species@1", "Species#2", "Species$3"]),
             })
         );
     }
@@ -196,7 +203,8 @@ mod tests {
                 blocks: vec![NexusBlock::TaxaBlock(
                     2,
                     vec!["Species 1".to_string(), "Species 2".to_string()]
-                )]
+                )],
+                taxa: Taxa::from_iter(["Species 1", "Species 2"]),
             })
         );
     }
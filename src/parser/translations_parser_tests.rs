@@ -5,7 +5,7 @@ mod tests {
     use crate::{
         lexer::{lexer::Lexer, tokens::Tokens},
         parser::parser::{Parser, ParsingError},
-        types::nexus::NexusBlock,
+        types::{nexus::NexusBlock, taxa::TaxonId, tree::TreeNode},
     };
 
     #[test]
@@ -36,15 +36,15 @@ mod tests {
                 HashMap::from([
                     (
                         "some very arbitrary text & some weird symbols cool:".to_string(),
-                        "Apes".to_string()
+                        0
                     ),
                     (
                         "some other arbitrary text + some other symbols-".to_string(),
-                        "Humans".to_string()
+                        1
                     ),
-                    ("Gorilla".to_string(), "Gorilla 1".to_string()),
-                    ("Gorilla2".to_string(), "Gorilla, 2;".to_string()),
-                    ("Schimpansen".to_string(), "Chimpanz''ee".to_string()),
+                    ("Gorilla".to_string(), 2),
+                    ("Gorilla2".to_string(), 3),
+                    ("Schimpansen".to_string(), 4),
                 ]),
                 vec![]
             ))
@@ -73,14 +73,14 @@ mod tests {
                 HashMap::from([
                     (
                         "some very arbitrary text & some weird symbols cool:".to_string(),
-                        "Apes".to_string()
+                        0
                     ),
                     (
                         "some other arbitrary text + some other symbols-".to_string(),
-                        "Humans".to_string()
+                        1
                     ),
-                    ("Gorilla".to_string(), "Gorilla".to_string()),
-                    ("Schimpansen".to_string(), "Chimpanz''ee".to_string()),
+                    ("Gorilla".to_string(), 2),
+                    ("Schimpansen".to_string(), 3),
                 ]),
                 vec![]
             ))
@@ -112,10 +112,10 @@ mod tests {
             result.blocks.get(1),
             Some(&NexusBlock::TreesBlock(
                 HashMap::from([
-                    ("0".to_string(), "1".to_string()),
-                    ("1".to_string(), "2".to_string()),
-                    ("2".to_string(), "3".to_string()),
-                    ("3".to_string(), "4".to_string()),
+                    ("0".to_string(), 0),
+                    ("1".to_string(), 1),
+                    ("2".to_string(), 2),
+                    ("3".to_string(), 3),
                 ]),
                 vec![]
             ))
@@ -143,7 +143,7 @@ mod tests {
             Some(&NexusBlock::TreesBlock(
                 HashMap::from([(
                     "some very arbitrary text & some weird symbols cool:".to_string(),
-                    "Apes".to_string()
+                    0
                 ),]),
                 vec![]
             ))
@@ -224,9 +224,51 @@ mod tests {
         assert_eq!(
             result.blocks.get(1),
             Some(&NexusBlock::TreesBlock(
-                HashMap::<String, String>::from([]),
+                HashMap::<String, TaxonId>::from([]),
                 vec![]
             ))
         );
     }
+
+    #[test]
+    fn test_translated_leaves_refer_to_taxa_block() {
+        let text = "#NEXUS
+        BEGIN taxa;
+            DIMENSIONS ntax=3;
+            TAXLABELS Apes Humans Gorillas;
+        END;
+
+        BEGIN trees;
+            Translate
+                1 Gorillas,
+                2 Apes,
+                3 Humans;
+            TREE t1 = ((2, 3), 1);
+            TREE t2 = (Apes, (3, Gorillas));
+        END;
+        ";
+        let lexer = Lexer::new(text);
+        let tokens = Tokens::new(&lexer);
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse().unwrap();
+
+        assert_eq!(result.taxa.labels(), &["Apes", "Humans", "Gorillas"]);
+
+        let Some(NexusBlock::TreesBlock(_, trees)) = result.blocks.get(1) else {
+            panic!("Expected a trees block.");
+        };
+        let leaves = trees
+            .iter()
+            .map(|tree| {
+                tree.tree
+                    .iter()
+                    .filter_map(|node| match node.get() {
+                        TreeNode::Leaf { taxon, .. } => Some(*taxon),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(leaves, vec![vec![0, 1, 2], vec![0, 1, 2]]);
+    }
 }
@@ -0,0 +1,174 @@
+use std::{borrow::Cow, io::BufRead, path::Path};
+
+use crate::{
+    input::{InputError, open_decompressed},
+    lexer::{
+        lexer::Lexer,
+        tokens::{Token, Tokens},
+    },
+    parser::parser::{Parser, ParsingError, TranslatedTaxa},
+    types::{nexus::Nexus, taxa::Taxa, tree::Tree},
+};
+
+/// Reads the trees of the first trees block of a NEXUS file one TREE statement at a
+/// time, so that large (and possibly compressed) posterior samples never have to be
+/// held in memory as a whole. Blocks after the first trees block are not read.
+pub struct TreeReader<R: BufRead> {
+    reader: R,
+    header: Nexus,
+    translated_taxa: TranslatedTaxa<'static>,
+    next_statement: Option<String>,
+    finished: bool,
+}
+
+impl TreeReader<Box<dyn BufRead>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        Self::new(open_decompressed(path)?)
+    }
+}
+
+impl<R: BufRead> TreeReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, InputError> {
+        let mut header = String::new();
+        let mut in_trees_block = false;
+        let mut next_statement = None;
+
+        while let Some(statement) = read_statement(&mut reader)? {
+            if in_trees_block
+                && (starts_with_keywords(&statement, &["tree"])
+                    || starts_with_keywords(&statement, &["end"]))
+            {
+                next_statement = Some(statement);
+                break;
+            }
+
+            in_trees_block |= starts_with_keywords(&statement, &["begin", "trees"]);
+            header.push_str(&statement);
+        }
+
+        let lexer = Lexer::new(&header);
+        let mut parser = Parser::new(Tokens::new(&lexer));
+        let header = parser.parse_trees_header()?;
+        let translated_taxa = parser
+            .translated_taxa()
+            .iter()
+            .map(|(key, taxon)| (Cow::Owned(key.to_string()), *taxon))
+            .collect();
+
+        Ok(Self {
+            reader,
+            header,
+            translated_taxa,
+            next_statement,
+            finished: !in_trees_block,
+        })
+    }
+
+    /// The blocks preceding the trees, including the trees block with its
+    /// translations but without trees.
+    pub fn header(&self) -> &Nexus {
+        &self.header
+    }
+
+    /// The taxa of the file, including the ones first seen in the trees read so far.
+    pub fn taxa(&self) -> &Taxa {
+        &self.header.taxa
+    }
+
+    fn parse_tree(&mut self, statement: &str) -> Result<Tree, ParsingError> {
+        let lexer = Lexer::new(statement);
+        let taxa = std::mem::take(&mut self.header.taxa);
+
+        let mut parser = Parser::with_taxa(Tokens::new(&lexer), taxa, &self.translated_taxa);
+        let tree = parser.parse_tree();
+
+        self.header.taxa = parser.into_taxa();
+        tree
+    }
+}
+
+impl<R: BufRead> Iterator for TreeReader<R> {
+    type Item = Result<Tree, InputError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let statement = match self.next_statement.take() {
+            Some(statement) => Ok(Some(statement)),
+            None => read_statement(&mut self.reader),
+        };
+
+        match statement {
+            Ok(Some(statement)) if starts_with_keywords(&statement, &["tree"]) => {
+                let tree = self.parse_tree(&statement).map_err(InputError::from);
+                self.finished = tree.is_err();
+                Some(tree)
+            }
+            Ok(Some(statement)) if starts_with_keywords(&statement, &["end"]) => {
+                self.finished = true;
+                None
+            }
+            Ok(_) => {
+                self.finished = true;
+                Some(Err(ParsingError::MissingToken(String::from("end")).into()))
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Reads up to and including the next `;` which is neither part of a quoted word
+/// nor of a comment. Returns `None` once only whitespace is left.
+fn read_statement(reader: &mut impl BufRead) -> Result<Option<String>, InputError> {
+    let mut statement = vec![];
+    let mut in_quotes = false;
+    let mut comment_depth = 0;
+
+    loop {
+        let start = statement.len();
+        if reader.read_until(b';', &mut statement)? == 0 {
+            break;
+        }
+
+        for byte in &statement[start..] {
+            match byte {
+                b'\'' if comment_depth == 0 => in_quotes = !in_quotes,
+                b'[' if !in_quotes => comment_depth += 1,
+                b']' if !in_quotes && comment_depth > 0 => comment_depth -= 1,
+                _ => {}
+            }
+        }
+
+        if statement.ends_with(b";") && !in_quotes && comment_depth == 0 {
+            break;
+        }
+    }
+
+    if statement.iter().all(|byte| byte.is_ascii_whitespace()) {
+        return Ok(None);
+    }
+
+    String::from_utf8(statement)
+        .map(Some)
+        .map_err(|_| InputError::InvalidUtf8)
+}
+
+/// Whether the statement starts with the given keywords, ignoring case, whitespace,
+/// comments and the #NEXUS tag.
+fn starts_with_keywords(statement: &str, keywords: &[&str]) -> bool {
+    let lexer = Lexer::new(statement);
+    let mut words = Tokens::new(&lexer)
+        .filter(|token| !matches!(token, Token::Whitespace(_) | Token::Comment(_)))
+        .skip_while(
+            |token| matches!(token, Token::Word(word) if word.eq_ignore_ascii_case("#NEXUS")),
+        );
+
+    keywords.iter().all(|keyword| {
+        matches!(words.next(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    })
+}
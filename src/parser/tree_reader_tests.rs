@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        input::InputError,
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::{
+            parser::{Parser, ParsingError},
+            tree_reader::TreeReader,
+        },
+        types::nexus::NexusBlock,
+    };
+
+    const TREES: &str = "#NEXUS
+        BEGIN taxa;
+            DIMENSIONS ntax=3;
+            TAXLABELS Apes Humans Gorillas;
+        END;
+
+        BEGIN trees;
+            Translate
+                1 Apes,
+                2 Humans,
+                3 'Gorillas';
+            TREE t1 = ((1:1, 2:2):0.5, 3:3);
+            TREE t2 = (Chimpanzees, (3, (2, 1)));
+            TREE t3 = (1, (Bonobos, 'Gorillas'));
+        END;
+        ";
+
+    #[test]
+    fn test_tree_reader() {
+        let lexer = Lexer::new(TREES);
+        let expected = Parser::new(Tokens::new(&lexer)).parse().unwrap();
+        let Some(NexusBlock::TreesBlock(translations, expected_trees)) = expected.blocks.get(1)
+        else {
+            panic!("Expected a trees block.");
+        };
+
+        let mut reader = TreeReader::new(Cursor::new(TREES)).unwrap();
+        let trees = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(&trees, expected_trees);
+        assert_eq!(reader.taxa(), &expected.taxa);
+        assert_eq!(
+            reader.header().blocks.get(1),
+            Some(&NexusBlock::TreesBlock(translations.clone(), vec![]))
+        );
+    }
+
+    #[test]
+    fn test_tree_reader_with_semicolons_in_quotes() {
+        let text = "#NEXUS
+        BEGIN trees;
+            TREE t1 = ('Apes;':1, Humans);
+            TREE 't;2' = (Humans, 'Apes;');
+        END;";
+
+        let trees = TreeReader::new(Cursor::new(text))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            trees.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["t1", "t;2"]
+        );
+    }
+
+    #[test]
+    fn test_tree_reader_without_trees_block() {
+        let text = "#NEXUS
+        BEGIN taxa;
+            DIMENSIONS ntax=2;
+            TAXLABELS Apes Humans;
+        END;
+        ";
+
+        let mut reader = TreeReader::new(Cursor::new(text)).unwrap();
+
+        assert!(reader.next().is_none());
+        assert_eq!(reader.taxa().labels(), &["Apes", "Humans"]);
+    }
+
+    #[test]
+    fn test_tree_reader_without_end() {
+        let text = "#NEXUS
+        BEGIN trees;
+            TREE t1 = (Apes, Humans);
+        ";
+
+        let mut reader = TreeReader::new(Cursor::new(text)).unwrap();
+
+        assert!(matches!(reader.next(), Some(Ok(_))));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(InputError::Parsing(ParsingError::MissingToken(_))))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_tree_reader_with_invalid_header() {
+        let text = "#NEXUS
+        BEGIN trees;
+            Translate 1 Apes, 1 Humans;
+            TREE t1 = (1, Humans);
+        END;";
+
+        assert!(matches!(
+            TreeReader::new(Cursor::new(text)),
+            Err(InputError::Parsing(ParsingError::DuplicateTranslations))
+        ));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_tree_reader_with_gzip_file() {
+        use std::{fs, io::Write};
+
+        let path = std::env::temp_dir().join("nexus_test_tree_reader.trees.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(fs::File::create(&path).unwrap(), Default::default());
+        encoder.write_all(TREES.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let trees = TreeReader::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            trees.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["t1", "t2", "t3"]
+        );
+    }
+}
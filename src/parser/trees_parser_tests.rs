@@ -13,8 +13,8 @@ mod tests {
         },
     };
 
-    impl<'a> Tree {
-        pub fn new(name: &'a str, rooted: bool) -> Tree {
+    impl Tree {
+        pub fn new(name: &str, rooted: bool) -> Tree {
             Tree {
                 tree: Arena::new(),
                 name: name.to_string(),
@@ -41,9 +41,7 @@ mod tests {
         let result = parser.parse().unwrap();
 
         let mut expected_tree = Tree::new("t1", false);
-        expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Apes".to_string()));
+        expected_tree.tree.new_node(TreeNode::new_leaf(0));
 
         assert_eq!(
             result.blocks.get(1),
@@ -70,16 +68,10 @@ mod tests {
 
         let mut expected_tree = Tree::new("t1", false);
 
-        let apes = expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Apes".to_string()));
-        let humans = expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Humans".to_string()));
+        let apes = expected_tree.tree.new_node(TreeNode::new_leaf(0));
+        let humans = expected_tree.tree.new_node(TreeNode::new_leaf(1));
         let apes_humans = expected_tree.tree.new_node(TreeNode::new_internal());
-        let gorillas = expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Gorillas".to_string()));
+        let gorillas = expected_tree.tree.new_node(TreeNode::new_leaf(2));
         let apes_humans_gorillas = expected_tree.tree.new_node(TreeNode::new_root());
 
         apes_humans_gorillas.append(apes_humans, &mut expected_tree.tree);
@@ -114,16 +106,10 @@ mod tests {
 
         let mut t1_expected_tree = Tree::new("t1", false);
 
-        let t1_apes = t1_expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Apes".to_string()));
-        let t1_humans = t1_expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Humans".to_string()));
+        let t1_apes = t1_expected_tree.tree.new_node(TreeNode::new_leaf(0));
+        let t1_humans = t1_expected_tree.tree.new_node(TreeNode::new_leaf(1));
         let t1_apes_humans = t1_expected_tree.tree.new_node(TreeNode::new_internal());
-        let t1_gorillas = t1_expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Gorillas".to_string()));
+        let t1_gorillas = t1_expected_tree.tree.new_node(TreeNode::new_leaf(2));
         let t1_apes_humans_gorillas = t1_expected_tree.tree.new_node(TreeNode::new_root());
 
         t1_apes_humans_gorillas.append(t1_apes_humans, &mut t1_expected_tree.tree);
@@ -134,15 +120,9 @@ mod tests {
 
         let mut t2_expected_tree = Tree::new("t2", false);
 
-        let t2_apes = t2_expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Apes".to_string()));
-        let t2_humans = t2_expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Humans".to_string()));
-        let t2_gorillas = t2_expected_tree
-            .tree
-            .new_node(TreeNode::new_leaf("Gorillas".to_string()));
+        let t2_apes = t2_expected_tree.tree.new_node(TreeNode::new_leaf(0));
+        let t2_humans = t2_expected_tree.tree.new_node(TreeNode::new_leaf(1));
+        let t2_gorillas = t2_expected_tree.tree.new_node(TreeNode::new_leaf(2));
         let t2_humans_gorillas = t2_expected_tree.tree.new_node(TreeNode::new_internal());
         let t2_apes_humans_gorillas = t2_expected_tree.tree.new_node(TreeNode::new_root());
 
@@ -208,16 +188,16 @@ mod tests {
 
         let apes = expected_tree
             .tree
-            .new_node(TreeNode::new_leaf("Apes".to_string()).with_length(1.0123));
+            .new_node(TreeNode::new_leaf(0).with_length(1.0123));
         let humans = expected_tree
             .tree
-            .new_node(TreeNode::new_leaf("Humans".to_string()).with_length(2.0));
+            .new_node(TreeNode::new_leaf(1).with_length(2.0));
         let apes_humans = expected_tree
             .tree
             .new_node(TreeNode::new_internal().with_length(0.10));
         let gorillas = expected_tree
             .tree
-            .new_node(TreeNode::new_leaf("Gorillas".to_string()).with_length(0.0025));
+            .new_node(TreeNode::new_leaf(2).with_length(0.0025));
         let apes_humans_gorillas = expected_tree.tree.new_node(TreeNode::new_root());
 
         apes_humans_gorillas.append(apes_humans, &mut expected_tree.tree);
@@ -231,4 +211,63 @@ mod tests {
             Some(&NexusBlock::TreesBlock(HashMap::new(), vec![expected_tree]))
         );
     }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_trees_block() {
+        let text = "#NEXUS
+        BEGIN taxa;
+            DIMENSIONS ntax=3;
+            TAXLABELS Apes Humans Gorillas;
+        END;
+
+        BEGIN trees;
+            Translate
+                1 Apes,
+                2 Humans;
+            TREE t1 = ((1:1, 2:2):0.5, Gorillas:3);
+            TREE t2 = (Chimpanzees, (Gorillas, (2, 1)));
+            TREE t3 = (1, (Bonobos, Gorillas));
+            TREE t4 = ((Chimpanzees, Bonobos), (Gorillas, Humans));
+        END;
+        ";
+
+        let lexer = Lexer::new(text);
+        let sequential = Parser::new(Tokens::new(&lexer)).parse().unwrap();
+        let parallel = Parser::new(Tokens::new(&lexer))
+            .with_parallel_trees(true)
+            .parse()
+            .unwrap();
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(
+            parallel.taxa.labels(),
+            &["Apes", "Humans", "Gorillas", "Chimpanzees", "Bonobos"]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_trees_block_with_malformed_tree() {
+        let text = "#NEXUS
+        BEGIN trees;
+            TREE t1 = ((Apes, Humans), Gorillas);
+            TREE t2 = ((Apes, Humans),);
+            TREE t3 = (Apes, (Humans, Gorillas));
+        END;
+        ";
+
+        let lexer = Lexer::new(text);
+        let mut sequential_parser = Parser::new(Tokens::new(&lexer));
+        let mut parallel_parser = Parser::new(Tokens::new(&lexer)).with_parallel_trees(true);
+        let sequential = sequential_parser.parse();
+        let parallel = parallel_parser.parse();
+
+        assert_eq!(sequential, Err(ParsingError::MalformedTreeStructure));
+        assert_eq!(parallel, sequential);
+        // the missing subtree after the comma
+        let position = text.find("),)").unwrap() + 2;
+        assert_eq!(sequential_parser.error_position(), Some(position));
+        assert_eq!(parallel_parser.error_position(), Some(position));
+    }
 }
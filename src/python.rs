@@ -0,0 +1,16 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+#[pyfunction]
+#[pyo3(name = "parse_file")]
+fn py_parse_file(path: &str) -> PyResult<()> {
+    crate::parse_file(path).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    Ok(())
+}
+
+#[pymodule]
+fn nexus(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(py_parse_file, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::fmt::Display;
+
+use crate::types::taxa::TaxonId;
+
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataType {
+    #[default]
+    Standard,
+    Dna,
+    Rna,
+    Nucleotide,
+    Protein,
+}
+
+impl DataType {
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.to_ascii_lowercase().as_str() {
+            "standard" => Some(DataType::Standard),
+            "dna" => Some(DataType::Dna),
+            "rna" => Some(DataType::Rna),
+            "nucleotide" => Some(DataType::Nucleotide),
+            "protein" => Some(DataType::Protein),
+            _ => None,
+        }
+    }
+
+    /// Guesses the datatype of sequences which do not declare it, e.g. the ones of a
+    /// PHYLIP or FASTA file. Sequences are nucleotides if at least 90% of their
+    /// states are A, C, G, T, U or N.
+    pub fn infer<'s>(
+        sequences: impl IntoIterator<Item = &'s str>,
+        missing: char,
+        gap: char,
+    ) -> Self {
+        let (mut states, mut nucleotides, mut digits, mut uracils, mut thymines) = (0, 0, 0, 0, 0);
+
+        for state in sequences
+            .into_iter()
+            .flat_map(|sequence| sequence.chars())
+            .filter(|state| *state != missing && *state != gap)
+            .map(|state| state.to_ascii_uppercase())
+        {
+            states += 1;
+            match state {
+                'U' => uracils += 1,
+                'T' => thymines += 1,
+                _ => {}
+            }
+            if "ACGTUN".contains(state) {
+                nucleotides += 1;
+            }
+            if state.is_ascii_digit() {
+                digits += 1;
+            }
+        }
+
+        if states == 0 || digits == states {
+            DataType::Standard
+        } else if nucleotides * 10 >= states * 9 {
+            match uracils > 0 && thymines == 0 {
+                true => DataType::Rna,
+                false => DataType::Dna,
+            }
+        } else {
+            DataType::Protein
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Standard => write!(f, "Standard"),
+            DataType::Dna => write!(f, "DNA"),
+            DataType::Rna => write!(f, "RNA"),
+            DataType::Nucleotide => write!(f, "Nucleotide"),
+            DataType::Protein => write!(f, "Protein"),
+        }
+    }
+}
+
+/// The aligned sequences of a CHARACTERS (or DATA) block, in the order of the
+/// matrix. Sequences are kept as written, including polymorphisms such as `{AG}`.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CharacterMatrix {
+    pub datatype: DataType,
+    pub missing: char,
+    pub gap: char,
+    pub sequences: Vec<(TaxonId, String)>,
+}
+
+impl CharacterMatrix {
+    pub fn new(datatype: DataType) -> Self {
+        Self {
+            datatype,
+            missing: '?',
+            gap: '-',
+            sequences: vec![],
+        }
+    }
+
+    pub fn with_missing(mut self, missing: char) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    pub fn with_gap(mut self, gap: char) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// The number of characters, i.e. the length of the sequences.
+    pub fn nchar(&self) -> usize {
+        self.sequences
+            .first()
+            .map_or(0, |(_, sequence)| character_count(sequence))
+    }
+
+    pub fn sequence(&self, taxon: TaxonId) -> Option<&str> {
+        self.sequences
+            .iter()
+            .find(|(id, _)| *id == taxon)
+            .map(|(_, sequence)| sequence.as_str())
+    }
+}
+
+/// Counts the characters of a sequence, where a polymorphism or uncertainty such as
+/// `{AG}` or `(AG)` is a single character.
+pub(crate) fn character_count(sequence: &str) -> usize {
+    let mut count = 0;
+    let mut in_group = false;
+
+    for state in sequence.chars() {
+        match state {
+            '{' | '(' => in_group = true,
+            '}' | ')' => {
+                in_group = false;
+                count += 1;
+            }
+            _ if !in_group => count += 1,
+            _ => {}
+        }
+    }
+
+    count
+}
+
+/// PHYLIP flavours. Strict PHYLIP pads or truncates taxon names to 10 characters,
+/// relaxed PHYLIP separates them from the sequence by whitespace. Interleaved files
+/// split the sequences into blocks where only the first one carries the names.
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct PhylipFormat {
+    pub strict: bool,
+    pub interleaved: bool,
+}
+
+/// How to read PHYLIP and FASTA files, which record neither their datatype nor
+/// their missing and gap symbols. The datatype is inferred if it is not given.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct AlignmentOptions {
+    pub datatype: Option<DataType>,
+    pub missing: char,
+    pub gap: char,
+}
+
+impl Default for AlignmentOptions {
+    fn default() -> Self {
+        Self {
+            datatype: None,
+            missing: '?',
+            gap: '-',
+        }
+    }
+}
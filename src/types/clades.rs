@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use fixedbitset::FixedBitSet;
+use indextree::NodeId;
+
+use crate::types::tree::Tree;
+
+/// A set of taxa with one bit per [`TaxonId`](crate::types::taxa::TaxonId), i.e.
+/// indexed in TAXA block order. Clades and splits of trees of the same file only
+/// compare equal if they were built with the same number of taxa.
+pub type Clade = FixedBitSet;
+
+/// The topology of a tree, i.e. its taxa and its clades (rooted) or non-trivial
+/// splits (unrooted) in a canonical order. Two trees have equal topologies, with
+/// equal hashes, if they only differ in branch lengths, labels, attributes or the
+/// order of children.
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone)]
+pub struct Topology {
+    pub taxa: Clade,
+    pub clades: Vec<Clade>,
+}
+
+impl Tree {
+    /// The clade below every node, in postorder. Clades have `taxa_count` bits,
+    /// which should be the number of taxa of the file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a leaf refers to a taxon not below `taxa_count`.
+    pub fn node_clades(&self, taxa_count: usize) -> Vec<(NodeId, Clade)> {
+        let mut clades: Vec<Option<Clade>> = vec![None; self.tree.count()];
+        let index = |node_id: NodeId| usize::from(node_id) - 1;
+
+        let postorder: Vec<NodeId> = self.postorder().collect();
+        for node_id in &postorder {
+            let mut clade = Clade::with_capacity(taxa_count);
+            if let Some(taxon) = self.node(*node_id).taxon() {
+                clade.insert(taxon);
+            }
+            for child_id in self.children(*node_id) {
+                if let Some(child_clade) = &clades[index(child_id)] {
+                    clade.union_with(child_clade);
+                }
+            }
+            clades[index(*node_id)] = Some(clade);
+        }
+
+        postorder
+            .into_iter()
+            .filter_map(|node_id| Some((node_id, clades[index(node_id)].take()?)))
+            .collect()
+    }
+
+    /// The clades of the internal nodes of the tree seen as rooted, including the
+    /// root, in postorder.
+    pub fn clades(&self, taxa_count: usize) -> Vec<Clade> {
+        self.node_clades(taxa_count)
+            .into_iter()
+            .filter(|(node_id, _)| !self.is_leaf(*node_id))
+            .map(|(_, clade)| clade)
+            .collect()
+    }
+
+    /// The canonical topology of the tree, seen as unrooted if `unrooted` is set,
+    /// in which case all rootings of the tree have the same topology.
+    pub fn topology(&self, taxa_count: usize, unrooted: bool) -> Topology {
+        let mut clades = self.clades(taxa_count);
+        let taxa = clades.last().cloned().unwrap_or_default();
+        if unrooted {
+            clades = self.splits(taxa_count);
+        }
+        clades.sort_unstable();
+        clades.dedup();
+
+        Topology { taxa, clades }
+    }
+
+    /// The non-trivial splits of the tree seen as unrooted, i.e. the ones with at
+    /// least two taxa on both sides. Splits are normalized (see
+    /// [`Tree::split_lengths`]), deduplicated and sorted.
+    pub fn splits(&self, taxa_count: usize) -> Vec<Clade> {
+        let mut splits: Vec<Clade> = self
+            .normalized_splits(taxa_count)
+            .filter(|(_, split, taxa_in_tree)| {
+                let size = split.count_ones(..);
+                size >= 2 && taxa_in_tree - size >= 2
+            })
+            .map(|(_, split, _)| split)
+            .collect();
+        splits.sort_unstable();
+        splits.dedup();
+        splits
+    }
+
+    /// All splits of the tree seen as unrooted, including the ones of the leaves,
+    /// with the length of their branch. Every split is normalized to the side that
+    /// does not contain the first taxon of the tree, so that it does not depend on
+    /// the rooting. The two branches below a bifurcating root induce the same split,
+    /// so their lengths are added up.
+    pub fn split_lengths(&self, taxa_count: usize) -> HashMap<Clade, f64> {
+        let mut splits = HashMap::new();
+
+        for (node_id, split, _) in self.normalized_splits(taxa_count) {
+            let length = self.node(node_id).length().unwrap_or_default();
+            *splits.entry(split).or_insert(0.0) += length;
+        }
+
+        splits
+    }
+
+    /// The normalized split (see [`Tree::split_lengths`]) above every node but the
+    /// root, in postorder. Nodes whose split contains all taxa of the tree, such as
+    /// a child of the root with all leaves below it, are left out.
+    pub fn node_splits(&self, taxa_count: usize) -> Vec<(NodeId, Clade)> {
+        self.normalized_splits(taxa_count)
+            .map(|(node_id, split, _)| (node_id, split))
+            .collect()
+    }
+
+    /// The normalized split of every node but the root, together with the number
+    /// of taxa in the tree.
+    fn normalized_splits(&self, taxa_count: usize) -> impl Iterator<Item = (NodeId, Clade, usize)> {
+        let mut node_clades = self.node_clades(taxa_count);
+        let all_taxa = node_clades
+            .pop()
+            .map(|(_, clade)| clade)
+            .unwrap_or_default();
+        let first_taxon = all_taxa.minimum();
+        let taxa_in_tree = all_taxa.count_ones(..);
+
+        node_clades
+            .into_iter()
+            .filter_map(move |(node_id, mut split)| {
+                if first_taxon.is_some_and(|taxon| split.contains(taxon)) {
+                    split.symmetric_difference_with(&all_taxa);
+                }
+
+                let size = split.count_ones(..);
+                (size > 0 && size < taxa_in_tree).then_some((node_id, split, taxa_in_tree))
+            })
+    }
+}
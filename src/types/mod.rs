@@ -1,2 +1,11 @@
+pub mod characters;
+pub mod clades;
+pub mod network;
 pub mod nexus;
+pub mod operations;
+mod operations_tests;
+pub mod taxa;
+pub mod time;
+mod time_tests;
 pub mod tree;
+mod tree_tests;
@@ -0,0 +1,79 @@
+use crate::types::taxa::TaxonId;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum NetworkNode {
+    Leaf {
+        taxon: TaxonId,
+    },
+    InternalNode {
+        label: Option<String>,
+    },
+    /// A reticulation such as `#H1` or `#LGT2`. All occurrences of the same hybrid
+    /// in an extended Newick string are merged into one node with several parents.
+    HybridNode {
+        hybrid: String,
+        label: Option<String>,
+    },
+    Root {
+        label: Option<String>,
+    },
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct NetworkEdge {
+    pub parent: usize,
+    pub child: usize,
+    pub length: Option<f64>,
+    pub support: Option<f64>,
+    /// The inheritance probability γ of the edge.
+    pub gamma: Option<f64>,
+}
+
+/// A phylogenetic network parsed from extended Newick. Nodes are referred to by
+/// their index in `nodes`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Network {
+    pub nodes: Vec<NetworkNode>,
+    pub edges: Vec<NetworkEdge>,
+    pub name: String,
+    pub rooted: bool,
+}
+
+impl Network {
+    pub fn new(name: String) -> Self {
+        Network {
+            nodes: vec![],
+            edges: vec![],
+            name,
+            rooted: false,
+        }
+    }
+
+    pub fn add_node(&mut self, node: NetworkNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// The node without parents.
+    pub fn root(&self) -> Option<usize> {
+        (0..self.nodes.len()).find(|node| self.parent_edges(*node).next().is_none())
+    }
+
+    pub fn parent_edges(&self, node: usize) -> impl Iterator<Item = &NetworkEdge> {
+        self.edges.iter().filter(move |edge| edge.child == node)
+    }
+
+    pub fn child_edges(&self, node: usize) -> impl Iterator<Item = &NetworkEdge> {
+        self.edges.iter().filter(move |edge| edge.parent == node)
+    }
+
+    /// The nodes with more than one parent.
+    pub fn reticulations(&self) -> impl Iterator<Item = usize> {
+        (0..self.nodes.len()).filter(|node| self.parent_edges(*node).nth(1).is_some())
+    }
+
+    /// Whether the network has no reticulations and thus is a tree.
+    pub fn is_tree(&self) -> bool {
+        self.reticulations().next().is_none()
+    }
+}
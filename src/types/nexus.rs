@@ -1,11 +1,22 @@
 use std::collections::HashMap;
 
-use crate::{parser::parser::ParsingError, types::tree::Tree};
+use crate::{
+    parser::parser::ParsingError,
+    types::{
+        characters::{CharacterMatrix, character_count},
+        taxa::{Taxa, TaxonId},
+        tree::Tree,
+    },
+};
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NexusBlock {
     TaxaBlock(usize, Vec<String>),
-    TreesBlock(HashMap<String, String>, Vec<Tree>),
+    /// The translations of the labels used in the trees to the taxa they stand for,
+    /// and the trees.
+    TreesBlock(HashMap<String, TaxonId>, Vec<Tree>),
+    CharactersBlock(CharacterMatrix),
 }
 
 impl NexusBlock {
@@ -20,11 +31,11 @@ impl NexusBlock {
         }
     }
     pub fn build_trees_block(
-        translations: HashMap<String, String>,
+        translations: HashMap<String, TaxonId>,
         trees: Vec<Tree>,
     ) -> Result<NexusBlock, ParsingError> {
         // verify that we have at most one translation per taxa
-        let mut unique_taxa_with_translation = translations.values().collect::<Vec<&String>>();
+        let mut unique_taxa_with_translation = translations.values().collect::<Vec<&TaxonId>>();
         unique_taxa_with_translation.sort();
         unique_taxa_with_translation.dedup();
         if translations.len() != unique_taxa_with_translation.len() {
@@ -41,22 +52,44 @@ impl NexusBlock {
 
         Ok(NexusBlock::TreesBlock(translations, trees))
     }
+    pub fn build_characters_block(
+        ntax: Option<usize>,
+        nchar: usize,
+        matrix: CharacterMatrix,
+    ) -> Result<NexusBlock, ParsingError> {
+        if ntax.is_some_and(|ntax| ntax != matrix.sequences.len())
+            || matrix
+                .sequences
+                .iter()
+                .any(|(_, sequence)| character_count(sequence) != nchar)
+        {
+            return Err(ParsingError::CharactersDimensionsMismatch);
+        }
+
+        Ok(NexusBlock::CharactersBlock(matrix))
+    }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Nexus {
     pub blocks: Vec<NexusBlock>,
+    pub taxa: Taxa,
 }
 
 impl Nexus {
-    pub fn build(blocks: Vec<NexusBlock>) -> Result<Self, ParsingError> {
-        let mut all_taxa: Vec<String> = vec![];
-        let mut all_translated_taxa: Vec<String> = vec![];
+    pub fn build(blocks: Vec<NexusBlock>, taxa: Taxa) -> Result<Self, ParsingError> {
+        let mut all_taxa: Vec<&str> = vec![];
+        let mut all_translated_taxa: Vec<&str> = vec![];
         for block in &blocks {
-            if let NexusBlock::TaxaBlock(_, taxa) = block {
-                all_taxa.extend(taxa.iter().cloned());
+            if let NexusBlock::TaxaBlock(_, labels) = block {
+                all_taxa.extend(labels.iter().map(String::as_str));
             } else if let NexusBlock::TreesBlock(translations, _) = block {
-                all_translated_taxa.extend(translations.values().map(|t| t.to_string()));
+                all_translated_taxa.extend(
+                    translations
+                        .values()
+                        .map(|taxon| taxa.label(*taxon).unwrap_or_default()),
+                );
             }
         }
 
@@ -66,10 +99,9 @@ impl Nexus {
             .filter(|t| !all_taxa.contains(t))
             .collect::<Vec<_>>();
         if !unknown_translated_taxa.is_empty() {
-            dbg!(all_translated_taxa.clone());
             return Err(ParsingError::TranslationForUnknownTaxa);
         }
 
-        Ok(Nexus { blocks })
+        Ok(Nexus { blocks, taxa })
     }
 }
@@ -0,0 +1,411 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    mem,
+};
+
+use indextree::NodeId;
+
+use crate::types::{
+    clades::Clade,
+    taxa::TaxonId,
+    tree::{Attributes, Tree, TreeNode},
+};
+
+#[derive(PartialEq, Debug)]
+pub enum RerootError {
+    UnknownTaxon(TaxonId),
+    InvalidOutgroup,
+}
+
+impl Display for RerootError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RerootError::UnknownTaxon(taxon) => write!(f, "Taxon {} is not in the tree", taxon),
+            RerootError::InvalidOutgroup => {
+                write!(f, "The outgroup is not a clade of the unrooted tree")
+            }
+        }
+    }
+}
+
+/// Operations changing the topology of a tree. Branch lengths are kept consistent,
+/// so the distances between the remaining leaves do not change unless stated
+/// otherwise. Labels of internal nodes, usually support values, describe the branch
+/// above their node and move with it when rerooting reverses branches; attributes
+/// stay with their nodes.
+impl Tree {
+    /// Makes an internal node the root. Rerooting at a leaf roots the tree on the
+    /// branch above it, at the leaf.
+    pub fn reroot_at_node(&mut self, node_id: NodeId) {
+        if self.is_leaf(node_id) {
+            self.reroot_on_edge(node_id, 0.0);
+            return;
+        }
+        let Some(old_root) = self.root() else {
+            return;
+        };
+        if node_id == old_root {
+            return;
+        }
+
+        self.reverse_path_to_root(node_id);
+        let node = mem::replace(self.tree[node_id].get_mut(), TreeNode::new_root());
+        *self.tree[node_id].get_mut() = TreeNode::Root {
+            label: None,
+            attributes: into_attributes(node),
+        };
+        self.suppress_if_unary(old_root);
+    }
+
+    /// Roots the tree on the branch above a node, `position` away from the node.
+    /// The position is clamped to the length of the branch, and both branches below
+    /// the new root get the label of the node.
+    pub fn reroot_on_edge(&mut self, node_id: NodeId, position: f64) {
+        let (Some(parent), Some(old_root)) = (self.parent(node_id), self.root()) else {
+            return;
+        };
+        let (length, label) = self.branch(node_id);
+        let (length, parent_length) = match length {
+            Some(length) => {
+                let position = position.clamp(0.0, length);
+                (Some(position), Some(length - position))
+            }
+            None => (None, None),
+        };
+
+        node_id.detach(&mut self.tree);
+        self.reverse_path_to_root(parent);
+
+        let root = self.tree.new_node(TreeNode::new_root());
+        root.append(node_id, &mut self.tree);
+        root.append(parent, &mut self.tree);
+        set_length(self.tree[node_id].get_mut(), length);
+        let parent_node = self.tree[parent].get_mut();
+        *parent_node = into_internal(mem::replace(parent_node, TreeNode::new_root()));
+        set_branch(parent_node, parent_length, label);
+
+        self.suppress_if_unary(old_root);
+    }
+
+    /// Roots the tree at the middle of the longest path between two leaves.
+    pub fn midpoint_root(&mut self) {
+        let Some(first_leaf) = self.leaves().next() else {
+            return;
+        };
+        let (start, _) = self.furthest_leaf(first_leaf);
+        let (end, diameter) = self.furthest_leaf(start);
+        if start == end {
+            return;
+        }
+
+        let end_ancestors: HashSet<NodeId> = end.ancestors(&self.tree).collect();
+        let Some(mrca) = start
+            .ancestors(&self.tree)
+            .find(|node_id| end_ancestors.contains(node_id))
+        else {
+            return;
+        };
+
+        // walk up from the leaf on whose side of the path the midpoint is
+        let mut remaining = diameter / 2.0;
+        let leaf = match self.distance_to_root(start) - self.distance_to_root(mrca) >= remaining {
+            true => start,
+            false => end,
+        };
+        let path: Vec<NodeId> = leaf
+            .ancestors(&self.tree)
+            .take_while(|node_id| *node_id != mrca)
+            .collect();
+        for (i, node_id) in path.iter().enumerate() {
+            let length = self.node(*node_id).length().unwrap_or_default();
+            if remaining <= length || i == path.len() - 1 {
+                self.reroot_on_edge(*node_id, remaining);
+                return;
+            }
+            remaining -= length;
+        }
+    }
+
+    /// Roots the tree at the middle of the branch separating the outgroup from the
+    /// other taxa. The outgroup has to be a clade of the tree seen as unrooted.
+    pub fn reroot_on_outgroup(&mut self, outgroup: &[TaxonId]) -> Result<(), RerootError> {
+        let taxa: Vec<TaxonId> = self
+            .leaves()
+            .filter_map(|leaf| self.node(leaf).taxon())
+            .collect();
+        let taxa_count = taxa.iter().max().map_or(0, |taxon| taxon + 1);
+
+        let mut outgroup_clade = Clade::with_capacity(taxa_count);
+        for taxon in outgroup {
+            if !taxa.contains(taxon) {
+                return Err(RerootError::UnknownTaxon(*taxon));
+            }
+            outgroup_clade.insert(*taxon);
+        }
+        let mut ingroup_clade = Clade::with_capacity(taxa_count);
+        ingroup_clade.extend(taxa.iter().copied());
+        ingroup_clade.difference_with(&outgroup_clade);
+        if outgroup_clade.is_clear() || ingroup_clade.is_clear() {
+            return Err(RerootError::InvalidOutgroup);
+        }
+
+        let (node_id, _) = self
+            .node_clades(taxa_count)
+            .into_iter()
+            .find(|(_, clade)| *clade == outgroup_clade || *clade == ingroup_clade)
+            .ok_or(RerootError::InvalidOutgroup)?;
+        self.reroot_at_branch_midpoint(node_id);
+
+        Ok(())
+    }
+
+    /// Removes the leaves of the given taxa. Nodes left without children are
+    /// removed and nodes left with a single child are suppressed.
+    pub fn prune(&mut self, taxa: &[TaxonId]) {
+        let leaves: Vec<NodeId> = self
+            .leaves()
+            .filter(|leaf| {
+                self.node(*leaf)
+                    .taxon()
+                    .is_some_and(|taxon| taxa.contains(&taxon))
+            })
+            .collect();
+
+        for leaf in leaves {
+            let mut parent = self.parent(leaf);
+            leaf.remove(&mut self.tree);
+
+            while let Some(node_id) = parent {
+                parent = self.parent(node_id);
+                match self.children(node_id).count() {
+                    0 if parent.is_some() => node_id.remove(&mut self.tree),
+                    1 => {
+                        self.suppress_if_unary(node_id);
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+    }
+
+    /// Orders the children of every node by their number of leaves, the smallest
+    /// subtrees first or, if `reverse`, last. Ties keep their order.
+    pub fn ladderize(&mut self, reverse: bool) {
+        let mut leaf_counts = HashMap::new();
+
+        for node_id in self.postorder().collect::<Vec<_>>() {
+            let mut children: Vec<(NodeId, usize)> = self
+                .children(node_id)
+                .map(|child| (child, leaf_counts[&child]))
+                .collect();
+            let leaf_count = match children.is_empty() {
+                true => 1,
+                false => children.iter().map(|(_, count)| count).sum(),
+            };
+            leaf_counts.insert(node_id, leaf_count);
+
+            match reverse {
+                true => children.sort_by_key(|(_, count)| std::cmp::Reverse(*count)),
+                false => children.sort_by_key(|(_, count)| *count),
+            }
+            for (child, _) in children {
+                child.detach(&mut self.tree);
+                node_id.append(child, &mut self.tree);
+            }
+        }
+    }
+
+    /// Removes the internal branches shorter than the threshold, turning their
+    /// nodes into polytomies. The length of a removed branch is added to the
+    /// branches below it.
+    pub fn collapse_short_branches(&mut self, threshold: f64) {
+        let short: Vec<NodeId> = self
+            .preorder()
+            .filter(|node_id| {
+                !self.is_leaf(*node_id)
+                    && self
+                        .node(*node_id)
+                        .length()
+                        .is_some_and(|length| length < threshold)
+            })
+            .collect();
+
+        for node_id in short {
+            let length = self.node(node_id).length().unwrap_or_default();
+            for child in self.children(node_id).collect::<Vec<_>>() {
+                let child = self.tree[child].get_mut();
+                let child_length = child.length();
+                set_length(
+                    child,
+                    child_length.map(|child_length| child_length + length),
+                );
+            }
+            node_id.remove(&mut self.tree);
+        }
+    }
+
+    /// Removes the nodes with a single child, merging the branches above and below
+    /// them. A root with a single internal child is replaced by that child.
+    pub fn suppress_unary_nodes(&mut self) {
+        let unary: Vec<NodeId> = self
+            .postorder()
+            .filter(|node_id| self.children(*node_id).count() == 1)
+            .collect();
+
+        for node_id in unary {
+            self.suppress_if_unary(node_id);
+        }
+    }
+
+    /// Roots the tree at the middle of the branch above a node. Below a bifurcating
+    /// root, the two branches of the root are taken as one.
+    fn reroot_at_branch_midpoint(&mut self, node_id: NodeId) {
+        let length = |node_id| self.node(node_id).length().unwrap_or_default();
+
+        let sibling = self.parent(node_id).and_then(|parent| {
+            let mut children = self.children(parent).filter(|child| *child != node_id);
+            match (self.parent(parent), children.next(), children.next()) {
+                (None, Some(sibling), None) => Some(sibling),
+                _ => None,
+            }
+        });
+
+        match sibling {
+            Some(sibling) => {
+                let midpoint = (length(node_id) + length(sibling)) / 2.0;
+                match midpoint <= length(node_id) {
+                    true => self.reroot_on_edge(node_id, midpoint),
+                    false => self.reroot_on_edge(sibling, midpoint),
+                }
+            }
+            None => self.reroot_on_edge(node_id, length(node_id) / 2.0),
+        }
+    }
+
+    fn suppress_if_unary(&mut self, node_id: NodeId) {
+        let children: Vec<NodeId> = self.children(node_id).collect();
+        let [child] = children[..] else {
+            return;
+        };
+
+        if self.parent(node_id).is_none() {
+            // a leaf cannot become the root
+            if self.is_leaf(child) {
+                return;
+            }
+            let child_node = mem::replace(self.tree[child].get_mut(), TreeNode::new_root());
+            *self.tree[child].get_mut() = TreeNode::Root {
+                label: None,
+                attributes: into_attributes(child_node),
+            };
+        } else {
+            let (length, label) = self.branch(node_id);
+            let (child_length, child_label) = self.branch(child);
+            let length = match (length, child_length) {
+                (None, None) => None,
+                (length, child_length) => {
+                    Some(length.unwrap_or_default() + child_length.unwrap_or_default())
+                }
+            };
+            let child_node = self.tree[child].get_mut();
+            set_branch(child_node, length, child_label.or(label));
+        }
+
+        node_id.remove(&mut self.tree);
+    }
+
+    /// Reverses the branches from the root down to a node, which becomes parentless.
+    /// Every node on the way gets the branch of the node below it.
+    fn reverse_path_to_root(&mut self, node_id: NodeId) {
+        let path: Vec<NodeId> = node_id.ancestors(&self.tree).collect();
+        let branches: Vec<(Option<f64>, Option<String>)> =
+            path.iter().map(|node_id| self.branch(*node_id)).collect();
+
+        for node_id in &path {
+            node_id.detach(&mut self.tree);
+        }
+        for (i, pair) in path.windows(2).enumerate() {
+            pair[0].append(pair[1], &mut self.tree);
+
+            let (length, label) = branches[i].clone();
+            let node = self.tree[pair[1]].get_mut();
+            *node = into_internal(mem::replace(node, TreeNode::new_root()));
+            set_branch(node, length, label);
+        }
+    }
+
+    /// The length and the label of the branch above a node.
+    fn branch(&self, node_id: NodeId) -> (Option<f64>, Option<String>) {
+        let node = self.node(node_id);
+        let label = match node {
+            TreeNode::InternalNode { label, .. } => label.clone(),
+            _ => None,
+        };
+        (node.length(), label)
+    }
+
+    /// The leaf furthest from the given leaf, with its distance.
+    fn furthest_leaf(&self, leaf: NodeId) -> (NodeId, f64) {
+        let mut furthest = (leaf, 0.0);
+        let mut stack = vec![(leaf, None, 0.0)];
+
+        while let Some((node_id, previous, distance)) = stack.pop() {
+            if self.is_leaf(node_id) && distance > furthest.1 {
+                furthest = (node_id, distance);
+            }
+
+            let neighbours = self
+                .children(node_id)
+                .map(|child| (child, self.node(child).length()))
+                .chain(
+                    self.parent(node_id)
+                        .map(|parent| (parent, self.node(node_id).length())),
+                );
+            for (neighbour, length) in neighbours {
+                if Some(neighbour) != previous {
+                    stack.push((
+                        neighbour,
+                        Some(node_id),
+                        distance + length.unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        furthest
+    }
+}
+
+fn into_internal(node: TreeNode) -> TreeNode {
+    match node {
+        TreeNode::Root { label, attributes } => TreeNode::InternalNode {
+            label,
+            length: None,
+            attributes,
+        },
+        node => node,
+    }
+}
+
+fn into_attributes(node: TreeNode) -> Attributes {
+    match node {
+        TreeNode::Leaf { attributes, .. }
+        | TreeNode::InternalNode { attributes, .. }
+        | TreeNode::Root { attributes, .. } => attributes,
+    }
+}
+
+fn set_length(node: &mut TreeNode, new_length: Option<f64>) {
+    if let TreeNode::Leaf { length, .. } | TreeNode::InternalNode { length, .. } = node {
+        *length = new_length;
+    }
+}
+
+fn set_branch(node: &mut TreeNode, new_length: Option<f64>, new_label: Option<String>) {
+    set_length(node, new_length);
+    if let TreeNode::InternalNode { label, .. } = node {
+        *label = new_label;
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{
+            nexus::{Nexus, NexusBlock},
+            operations::RerootError,
+            tree::{AttributeValue, Tree},
+        },
+        writer::newick::write_newick,
+    };
+
+    const TREE: &str = "((A:1,B:2)90:3,(C:4,D:5)80[&rate=2]:6);";
+
+    fn parse(newick: &str) -> (Nexus, Tree) {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        let tree = match nexus.blocks.first() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees[0].clone(),
+            _ => panic!("Expected a trees block."),
+        };
+        (nexus, tree)
+    }
+
+    fn edit(newick: &str, operation: impl FnOnce(&mut Tree)) -> String {
+        let (nexus, mut tree) = parse(newick);
+        operation(&mut tree);
+        write_newick(&tree, &nexus.taxa)
+    }
+
+    #[test]
+    fn test_reroot() {
+        assert_eq!(
+            edit(TREE, |tree| tree
+                .reroot_at_node(tree.mrca(&[2, 3]).unwrap())),
+            "(C:4,D:5,(A:1,B:2)90:9)[&rate=2];"
+        );
+        assert_eq!(
+            edit(TREE, |tree| tree.reroot_on_edge(tree.leaf(3).unwrap(), 2.0)),
+            "(D:2,(C:4,(A:1,B:2)90:9)[&rate=2]:3);"
+        );
+        assert_eq!(
+            edit(TREE, |tree| tree.midpoint_root()),
+            "((C:4,D:5)80[&rate=2]:3,(A:1,B:2)90:6);"
+        );
+    }
+
+    #[test]
+    fn test_reroot_on_outgroup() {
+        let (nexus, mut tree) = parse(TREE);
+
+        tree.reroot_on_outgroup(&[0]).unwrap();
+        assert_eq!(
+            write_newick(&tree, &nexus.taxa),
+            "(A:0.5,(B:2,(C:4,D:5)80[&rate=2]:9):0.5);"
+        );
+
+        // the outgroup is not a clade of the rooted tree, but its complement is
+        tree.reroot_on_outgroup(&[0, 1]).unwrap();
+        assert_eq!(
+            write_newick(&tree, &nexus.taxa),
+            "((C:4,D:5)80[&rate=2]:4.5,(B:2,A:1)80:4.5);"
+        );
+
+        // both branches of a bifurcating root are one
+        tree.reroot_on_outgroup(&[2, 3]).unwrap();
+        assert_eq!(
+            write_newick(&tree, &nexus.taxa),
+            "((C:4,D:5)80[&rate=2]:4.5,(B:2,A:1)80:4.5);"
+        );
+
+        assert_eq!(
+            tree.reroot_on_outgroup(&[0, 2]),
+            Err(RerootError::InvalidOutgroup)
+        );
+        assert_eq!(
+            tree.reroot_on_outgroup(&[0, 1, 2, 3]),
+            Err(RerootError::InvalidOutgroup)
+        );
+        assert_eq!(
+            tree.reroot_on_outgroup(&[9]),
+            Err(RerootError::UnknownTaxon(9))
+        );
+    }
+
+    #[test]
+    fn test_reroot_keeps_distances_and_attributes() {
+        let (_, original) = parse(TREE);
+        let (_, mut tree) = parse(TREE);
+        tree.reroot_on_edge(tree.leaf(1).unwrap(), 0.5);
+
+        for a in 0..4 {
+            for b in 0..4 {
+                let distance = |tree: &Tree| {
+                    let mrca = tree.mrca(&[a, b]).unwrap();
+                    tree.distance_to_root(tree.leaf(a).unwrap())
+                        + tree.distance_to_root(tree.leaf(b).unwrap())
+                        - 2.0 * tree.distance_to_root(mrca)
+                };
+                assert_eq!(distance(&tree), distance(&original));
+            }
+        }
+
+        let c_d = tree.mrca(&[2, 3]).unwrap();
+        assert_eq!(
+            tree.node(c_d).attributes().get("rate"),
+            Some(&AttributeValue::Number(2.0))
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        assert_eq!(
+            edit(TREE, |tree| tree.prune(&[0])),
+            "(B:5,(C:4,D:5)80[&rate=2]:6);"
+        );
+        assert_eq!(
+            edit(TREE, |tree| tree.prune(&[0, 1])),
+            "(C:4,D:5)[&rate=2];"
+        );
+        assert_eq!(
+            edit("(((A,B),C),(D,E));", |tree| tree.prune(&[0, 1, 4])),
+            "(C,D);"
+        );
+    }
+
+    #[test]
+    fn test_ladderize() {
+        assert_eq!(
+            edit("((A,(B,C)),D);", |tree| tree.ladderize(false)),
+            "(D,(A,(B,C)));"
+        );
+        assert_eq!(
+            edit("((A,(B,C)),D);", |tree| tree.ladderize(true)),
+            "(((B,C),A),D);"
+        );
+    }
+
+    #[test]
+    fn test_collapse_and_suppress() {
+        assert_eq!(
+            edit("((A:1,B:1):0.5,(C:1,(D:1,E:2):0.25):2);", |tree| {
+                tree.collapse_short_branches(1.0)
+            }),
+            "(A:1.5,B:1.5,(C:1,D:1.25,E:2.25):2);"
+        );
+        assert_eq!(
+            edit("(((A:1)x:2,B:1):1);", |tree| tree.suppress_unary_nodes()),
+            "(A:3,B:1);"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+pub type TaxonId = usize;
+
+/// Interned taxon labels of a NEXUS file. Every label is stored once and leaves
+/// refer to their taxon by its [`TaxonId`], which is the position of the label in
+/// the order the taxa were first seen (i.e. the TAXA block order if there is one).
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct Taxa {
+    labels: Vec<String>,
+    ids: HashMap<String, TaxonId>,
+}
+
+impl Taxa {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_insert(&mut self, label: &str) -> TaxonId {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
+        }
+
+        let id = self.labels.len();
+        self.labels.push(label.to_string());
+        self.ids.insert(label.to_string(), id);
+        id
+    }
+
+    pub fn id(&self, label: &str) -> Option<TaxonId> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: TaxonId) -> Option<&str> {
+        self.labels.get(id).map(|label| label.as_str())
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Taxa {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut taxa = Taxa::new();
+        for label in iter {
+            taxa.get_or_insert(label.as_ref());
+        }
+        taxa
+    }
+}
+
+/// Taxa are serialized as their list of labels.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Taxa {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.labels.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Taxa {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, fmt::Display};
+
+use indextree::NodeId;
+use regex::Regex;
+
+use crate::types::{
+    taxa::{Taxa, TaxonId},
+    tree::{Tree, TreeNode},
+};
+
+#[derive(PartialEq, Debug)]
+pub enum TipDateError {
+    /// The name of a taxon has no valid date.
+    InvalidDate(String),
+}
+
+impl Display for TipDateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TipDateError::InvalidDate(taxon) => {
+                write!(f, "No valid date in the name of taxon {}", taxon)
+            }
+        }
+    }
+}
+
+/// Reads the dates of the taxa from their names, e.g. `2004.5` from `A_2004.5`.
+/// The date is the first capture group of the pattern, or the whole match if it
+/// has none. Dates are decimal years or ISO dates such as `2004-07-01` or
+/// `2004-07`, which are converted to decimal years from the start of their day or
+/// month.
+///
+/// Fails with the name of the first taxon without a valid date.
+pub fn parse_tip_dates(
+    taxa: &Taxa,
+    pattern: &Regex,
+) -> Result<HashMap<TaxonId, f64>, TipDateError> {
+    let mut dates = HashMap::new();
+
+    for (taxon, label) in taxa.labels().iter().enumerate() {
+        let date = pattern
+            .captures(label)
+            .and_then(|captures| captures.get(1).or_else(|| captures.get(0)))
+            .and_then(|date| decimal_year(date.as_str()))
+            .ok_or_else(|| TipDateError::InvalidDate(label.to_string()))?;
+        dates.insert(taxon, date);
+    }
+
+    Ok(dates)
+}
+
+/// Converts a decimal year or an ISO date to a decimal year.
+fn decimal_year(date: &str) -> Option<f64> {
+    if let Ok(year) = date.parse::<f64>() {
+        return Some(year);
+    }
+
+    let mut parts = date.split('-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month: usize = parts.next()?.parse().ok()?;
+    let day: usize = parts.next().map_or(Some(1), |day| day.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let mut days_in_months = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    if leap {
+        days_in_months[1] = 29;
+    }
+    if !(1..=12).contains(&month) || !(1..=days_in_months[month - 1]).contains(&day) {
+        return None;
+    }
+
+    let day_of_year: usize = days_in_months[..month - 1].iter().sum::<usize>() + day - 1;
+    let days_in_year = if leap { 366.0 } else { 365.0 };
+    Some(f64::from(year) + day_of_year as f64 / days_in_year)
+}
+
+impl Tree {
+    /// Sets the branch lengths so that the nodes have the given heights, i.e. the
+    /// length of every branch is the height of its parent minus the height of its
+    /// child. Branches with a node missing from `heights` are left unchanged.
+    pub fn set_node_heights(&mut self, heights: &HashMap<NodeId, f64>) {
+        let branches: Vec<(NodeId, NodeId)> = self
+            .preorder()
+            .filter_map(|node_id| Some((self.parent(node_id)?, node_id)))
+            .collect();
+
+        for (parent, node_id) in branches {
+            let (Some(parent_height), Some(height)) = (heights.get(&parent), heights.get(&node_id))
+            else {
+                continue;
+            };
+            if let TreeNode::Leaf { length, .. } | TreeNode::InternalNode { length, .. } =
+                self.tree[node_id].get_mut()
+            {
+                *length = Some(parent_height - height);
+            }
+        }
+    }
+
+    /// Whether the heights of the leaves match the dates of their taxa up to the
+    /// given absolute tolerance, i.e. every leaf is as much older than the most
+    /// recent leaf as its date says. Leaves without a date make the tree
+    /// inconsistent.
+    pub fn is_consistent_with_tip_dates(
+        &self,
+        dates: &HashMap<TaxonId, f64>,
+        tolerance: f64,
+    ) -> bool {
+        let Some(tip_dates) = self
+            .leaves()
+            .map(|leaf| Some((leaf, *dates.get(&self.node(leaf).taxon()?)?)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+
+        let most_recent = tip_dates
+            .iter()
+            .map(|(_, date)| *date)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let heights = self.node_heights();
+
+        tip_dates
+            .iter()
+            .all(|(leaf, date)| (heights[leaf] - (most_recent - date)).abs() <= tolerance)
+    }
+}
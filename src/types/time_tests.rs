@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{
+            nexus::{Nexus, NexusBlock},
+            taxa::Taxa,
+            time::{TipDateError, parse_tip_dates},
+            tree::Tree,
+        },
+    };
+
+    fn parse(newick: &str) -> (Nexus, Tree) {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        let tree = match nexus.blocks.first() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees[0].clone(),
+            _ => panic!("Expected a trees block."),
+        };
+        (nexus, tree)
+    }
+
+    #[test]
+    fn test_set_node_heights() {
+        let (_, mut tree) = parse("((A:1,B:2):1,C:1.5);");
+        let mut heights = tree.node_heights();
+        assert_eq!(heights[&tree.root().unwrap()], 3.0);
+        assert_eq!(heights[&tree.leaf(0).unwrap()], 1.0);
+        assert_eq!(heights[&tree.leaf(2).unwrap()], 1.5);
+
+        // make the tree ultrametric by moving all leaves to height 0
+        for leaf in tree.leaves().collect::<Vec<_>>() {
+            heights.insert(leaf, 0.0);
+        }
+        tree.set_node_heights(&heights);
+
+        let a = tree.leaf(0).unwrap();
+        assert_eq!(tree.node(a).length(), Some(2.0));
+        assert_eq!(tree.node(tree.parent(a).unwrap()).length(), Some(1.0));
+        assert_eq!(tree.node(tree.leaf(2).unwrap()).length(), Some(3.0));
+        assert!(tree.is_ultrametric(0.0));
+        assert_eq!(tree.node_heights(), heights);
+    }
+
+    #[test]
+    fn test_parse_tip_dates() {
+        let taxa = Taxa::from_iter(["A_2004.5", "B|2000-07-02", "C_2001-01"]);
+        let pattern = Regex::new(r"[_|]([\d.-]+)$").unwrap();
+        let dates = parse_tip_dates(&taxa, &pattern).unwrap();
+
+        assert_eq!(dates[&0], 2004.5);
+        assert_eq!(dates[&1], 2000.0 + 183.0 / 366.0);
+        assert_eq!(dates[&2], 2001.0);
+
+        let taxa = Taxa::from_iter(["A_2004", "B", "C_2001-02-29"]);
+        assert_eq!(
+            parse_tip_dates(&taxa, &pattern),
+            Err(TipDateError::InvalidDate("B".to_string()))
+        );
+        let taxa = Taxa::from_iter(["C_2001-02-29"]);
+        assert_eq!(
+            parse_tip_dates(&taxa, &pattern),
+            Err(TipDateError::InvalidDate("C_2001-02-29".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_tip_date_consistency() {
+        let (nexus, tree) = parse("((A_2010:2,B_2008:0):2,C_2011:5);");
+        let pattern = Regex::new(r"_(\d+)$").unwrap();
+        let dates = parse_tip_dates(&nexus.taxa, &pattern).unwrap();
+
+        assert!(tree.is_consistent_with_tip_dates(&dates, 1e-9));
+        assert!(!tree.is_ultrametric(1e-9));
+
+        let (_, shifted) = parse("((A_2010:2,B_2008:0.5):2,C_2011:5);");
+        assert!(!shifted.is_consistent_with_tip_dates(&dates, 0.1));
+        assert!(shifted.is_consistent_with_tip_dates(&dates, 0.5));
+
+        let mut missing = dates.clone();
+        missing.remove(&1);
+        assert!(!tree.is_consistent_with_tip_dates(&missing, 1.0));
+    }
+}
@@ -1,27 +1,66 @@
-use indextree::Arena;
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(PartialEq, Debug)]
+use indextree::{Arena, NodeEdge, NodeId};
+
+use crate::types::taxa::TaxonId;
+
+/// The value of a node attribute, e.g. of a `[&rate=1.2,height_95%_HPD={0.5,0.9}]`
+/// annotation as written by BEAST or TreeAnnotator.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum AttributeValue {
+    Number(f64),
+    Text(String),
+    List(Vec<AttributeValue>),
+}
+
+pub type Attributes = BTreeMap<String, AttributeValue>;
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
 pub enum TreeNode {
     Leaf {
-        label: String,
-        taxon: String,
+        taxon: TaxonId,
         length: Option<f64>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Attributes::is_empty")
+        )]
+        attributes: Attributes,
     },
     InternalNode {
         label: Option<String>,
         length: Option<f64>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Attributes::is_empty")
+        )]
+        attributes: Attributes,
     },
     Root {
         label: Option<String>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Attributes::is_empty")
+        )]
+        attributes: Attributes,
     },
 }
 
 impl TreeNode {
-    pub fn new_leaf(taxon: String) -> Self {
+    pub fn new_leaf(taxon: TaxonId) -> Self {
         TreeNode::Leaf {
-            label: taxon.to_string(),
             taxon,
             length: None,
+            attributes: Attributes::new(),
         }
     }
 
@@ -29,32 +68,676 @@ impl TreeNode {
         TreeNode::InternalNode {
             label: None,
             length: None,
+            attributes: Attributes::new(),
         }
     }
 
     pub fn new_root() -> Self {
-        TreeNode::Root { label: None }
+        TreeNode::Root {
+            label: None,
+            attributes: Attributes::new(),
+        }
+    }
+
+    pub fn with_label(mut self, new_label: String) -> Self {
+        match &mut self {
+            TreeNode::Leaf { .. } => {}
+            TreeNode::InternalNode { label, .. } | TreeNode::Root { label, .. } => {
+                *label = Some(new_label)
+            }
+        }
+        self
     }
 
-    pub fn with_length(self, length: f64) -> Self {
+    pub fn with_length(mut self, new_length: f64) -> Self {
+        match &mut self {
+            TreeNode::Leaf { length, .. } | TreeNode::InternalNode { length, .. } => {
+                *length = Some(new_length)
+            }
+            TreeNode::Root { .. } => {}
+        }
+        self
+    }
+
+    pub fn with_attribute(mut self, key: &str, value: AttributeValue) -> Self {
+        self.attributes_mut().insert(key.to_string(), value);
+        self
+    }
+
+    pub fn taxon(&self) -> Option<TaxonId> {
         match self {
-            TreeNode::Leaf { label, taxon, .. } => TreeNode::Leaf {
-                label,
-                taxon,
-                length: Some(length),
-            },
-            TreeNode::InternalNode { label, .. } => TreeNode::InternalNode {
-                label,
-                length: Some(length),
-            },
-            TreeNode::Root { label } => TreeNode::Root { label },
+            TreeNode::Leaf { taxon, .. } => Some(*taxon),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            TreeNode::Leaf { .. } => None,
+            TreeNode::InternalNode { label, .. } | TreeNode::Root { label, .. } => label.as_deref(),
+        }
+    }
+
+    /// The length of the branch above the node.
+    pub fn length(&self) -> Option<f64> {
+        match self {
+            TreeNode::Leaf { length, .. } | TreeNode::InternalNode { length, .. } => *length,
+            TreeNode::Root { .. } => None,
+        }
+    }
+
+    pub fn attributes(&self) -> &Attributes {
+        match self {
+            TreeNode::Leaf { attributes, .. }
+            | TreeNode::InternalNode { attributes, .. }
+            | TreeNode::Root { attributes, .. } => attributes,
+        }
+    }
+
+    pub fn attributes_mut(&mut self) -> &mut Attributes {
+        match self {
+            TreeNode::Leaf { attributes, .. }
+            | TreeNode::InternalNode { attributes, .. }
+            | TreeNode::Root { attributes, .. } => attributes,
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
+/// With the `serde` feature, a tree is serialized as nested nodes instead of its
+/// arena. Every node has a `type` (`root`, `internal_node` or `leaf`), its label or
+/// taxon, its length, its `attributes` unless there are none and, unless it is a
+/// leaf, its `children`:
+///
+/// ```json
+/// {
+///   "name": "t1",
+///   "rooted": false,
+///   "root": {
+///     "type": "root", "label": null,
+///     "children": [
+///       {"type": "internal_node", "label": "95", "length": 0.5, "children": [
+///         {"type": "leaf", "taxon": 0, "length": 1.0},
+///         {"type": "leaf", "taxon": 1, "length": 2.0}
+///       ]},
+///       {"type": "leaf", "taxon": 2, "length": 3.0}
+///     ]
+///   }
+/// }
+/// ```
+///
+/// Taxa are indices into the taxa of the Nexus, which are serialized as a list of
+/// labels. Deserializing rebuilds the arena in postorder, the order the parser
+/// creates nodes in, so the arena of a tree which was rerooted or pruned is laid
+/// out differently after a round trip; trees compare equal when their nodes do,
+/// whatever their arenas. Serializing and deserializing grow the stack as needed
+/// for deep trees, but serde_json limits the nesting depth to 128 by default,
+/// i.e. to trees about 64 nodes deep; see its `unbounded_depth` feature.
+#[derive(Debug, Clone)]
 pub struct Tree {
     pub tree: Arena<TreeNode>,
     pub name: String,
     pub rooted: bool,
 }
+
+impl PartialEq for Tree {
+    /// Compares the names, whether the trees are rooted and their nodes from the
+    /// root down, with the children of every node in order.
+    fn eq(&self, other: &Self) -> bool {
+        // the nodes in preorder, followed by None once their children were seen
+        fn edges(tree: &Tree) -> impl Iterator<Item = Option<&TreeNode>> {
+            let edges = tree
+                .root()
+                .into_iter()
+                .flat_map(|root| root.traverse(&tree.tree));
+            edges.map(|edge| match edge {
+                NodeEdge::Start(node_id) => Some(tree.node(node_id)),
+                NodeEdge::End(_) => None,
+            })
+        }
+        self.name == other.name && self.rooted == other.rooted && edges(self).eq(edges(other))
+    }
+}
+
+impl Tree {
+    /// The node without a parent. It is looked up rather than assumed to be at a
+    /// fixed position, so it does not depend on the order the arena was built in.
+    pub fn root(&self) -> Option<NodeId> {
+        self.tree
+            .iter()
+            .find(|node| !node.is_removed() && node.parent().is_none())
+            .and_then(|node| self.tree.get_node_id(node))
+    }
+
+    pub fn node(&self, node_id: NodeId) -> &TreeNode {
+        self.tree[node_id].get()
+    }
+
+    pub fn parent(&self, node_id: NodeId) -> Option<NodeId> {
+        self.tree[node_id].parent()
+    }
+
+    pub fn children(&self, node_id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        node_id.children(&self.tree)
+    }
+
+    pub fn is_leaf(&self, node_id: NodeId) -> bool {
+        self.tree[node_id].first_child().is_none()
+    }
+
+    /// The nodes from the root down, parents before their children.
+    pub fn preorder(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.root()
+            .into_iter()
+            .flat_map(|root| root.descendants(&self.tree))
+    }
+
+    /// The nodes from the leaves up, children before their parents.
+    pub fn postorder(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.root().into_iter().flat_map(|root| {
+            root.traverse(&self.tree).filter_map(|edge| match edge {
+                NodeEdge::End(node_id) => Some(node_id),
+                NodeEdge::Start(_) => None,
+            })
+        })
+    }
+
+    /// The leaves in preorder.
+    pub fn leaves(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.preorder().filter(|node_id| self.is_leaf(*node_id))
+    }
+
+    /// The leaf of a taxon.
+    pub fn leaf(&self, taxon: TaxonId) -> Option<NodeId> {
+        self.leaves()
+            .find(|node_id| self.node(*node_id).taxon() == Some(taxon))
+    }
+
+    /// The most recent common ancestor of the leaves of the given taxa, or `None`
+    /// if there are no taxa or one of them is not in the tree.
+    pub fn mrca(&self, taxa: &[TaxonId]) -> Option<NodeId> {
+        let (first, others) = taxa.split_first()?;
+
+        // the ancestors of the first leaf, shortened to the common ones
+        let mut ancestors: Vec<NodeId> = self.leaf(*first)?.ancestors(&self.tree).collect();
+        for taxon in others {
+            let common = self
+                .leaf(*taxon)?
+                .ancestors(&self.tree)
+                .find(|node_id| ancestors.contains(node_id))?;
+            let position = ancestors.iter().position(|node_id| *node_id == common)?;
+            ancestors.drain(..position);
+        }
+
+        ancestors.first().copied()
+    }
+
+    /// The sum of the branch lengths from the node up to the root. Missing lengths
+    /// count as 0.
+    pub fn distance_to_root(&self, node_id: NodeId) -> f64 {
+        node_id
+            .ancestors(&self.tree)
+            .filter_map(|node_id| self.node(node_id).length())
+            .sum()
+    }
+
+    /// The height of a node above the youngest leaf, i.e. the distance from the
+    /// root to the furthest leaf minus the distance from the root to the node.
+    pub fn node_height(&self, node_id: NodeId) -> f64 {
+        let tree_height = self
+            .leaves()
+            .map(|leaf| self.distance_to_root(leaf))
+            .fold(0.0, f64::max);
+
+        tree_height - self.distance_to_root(node_id)
+    }
+
+    /// The heights of all nodes, as by [`Tree::node_height`] but in a single pass.
+    pub fn node_heights(&self) -> HashMap<NodeId, f64> {
+        let mut distances: HashMap<NodeId, f64> = HashMap::new();
+        for node_id in self.preorder() {
+            let parent_distance = self
+                .parent(node_id)
+                .map_or(0.0, |parent| distances[&parent]);
+            let length = self.node(node_id).length().unwrap_or_default();
+            distances.insert(node_id, parent_distance + length);
+        }
+
+        let tree_height = self
+            .leaves()
+            .map(|leaf| distances[&leaf])
+            .fold(0.0, f64::max);
+        distances
+            .into_iter()
+            .map(|(node_id, distance)| (node_id, tree_height - distance))
+            .collect()
+    }
+
+    /// Whether all leaves are at the same distance from the root, up to the given
+    /// absolute tolerance.
+    pub fn is_ultrametric(&self, tolerance: f64) -> bool {
+        let (min, max) = self.leaves().map(|leaf| self.distance_to_root(leaf)).fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), distance| (min.min(distance), max.max(distance)),
+        );
+
+        min > max || max - min <= tolerance
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialization {
+    use std::fmt;
+
+    use indextree::{Arena, NodeId};
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer,
+        de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    };
+
+    use super::{Attributes, Tree, TreeNode};
+    use crate::types::taxa::TaxonId;
+
+    /// Nodes are (de)serialized recursively, growing the stack by this much
+    /// whenever less than a tenth of it is left, so that deep trees do not overflow
+    /// it.
+    const STACK_SIZE: usize = 1024 * 1024;
+
+    fn with_stack<R>(f: impl FnOnce() -> R) -> R {
+        stacker::maybe_grow(STACK_SIZE / 10, STACK_SIZE, f)
+    }
+
+    #[derive(Serialize)]
+    struct TreeRef<'t> {
+        name: &'t str,
+        rooted: bool,
+        root: Option<NodeRef<'t>>,
+    }
+
+    #[derive(Serialize)]
+    struct NodeRef<'t> {
+        #[serde(flatten)]
+        node: &'t TreeNode,
+        #[serde(skip_serializing_if = "Children::is_empty")]
+        children: Children<'t>,
+    }
+
+    struct Children<'t> {
+        tree: &'t Tree,
+        node_id: NodeId,
+    }
+
+    impl<'t> NodeRef<'t> {
+        fn new(tree: &'t Tree, node_id: NodeId) -> Self {
+            NodeRef {
+                node: tree.node(node_id),
+                children: Children { tree, node_id },
+            }
+        }
+    }
+
+    impl Children<'_> {
+        fn is_empty(&self) -> bool {
+            self.tree.is_leaf(self.node_id)
+        }
+    }
+
+    impl Serialize for Children<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let tree = self.tree;
+            with_stack(|| {
+                serializer.collect_seq(
+                    tree.children(self.node_id)
+                        .map(|child| NodeRef::new(tree, child)),
+                )
+            })
+        }
+    }
+
+    impl Serialize for Tree {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            TreeRef {
+                name: &self.name,
+                rooted: self.rooted,
+                root: self.root().map(|root| NodeRef::new(self, root)),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum NodeType {
+        Leaf,
+        InternalNode,
+        Root,
+    }
+
+    /// Deserializes a node and its descendants into the arena, children before
+    /// their parent as the parser creates them, and returns its id.
+    struct NodeSeed<'a> {
+        arena: &'a mut Arena<TreeNode>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+        type Value = NodeId;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<NodeId, D::Error> {
+            with_stack(|| deserializer.deserialize_map(self))
+        }
+    }
+
+    impl<'de> Visitor<'de> for NodeSeed<'_> {
+        type Value = NodeId;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a tree node")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<NodeId, A::Error> {
+            let mut node_type = None;
+            let mut taxon: Option<TaxonId> = None;
+            let mut label: Option<String> = None;
+            let mut length: Option<f64> = None;
+            let mut attributes = Attributes::new();
+            let mut children = vec![];
+
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "type" => node_type = Some(map.next_value::<NodeType>()?),
+                    "taxon" => taxon = Some(map.next_value()?),
+                    "label" => label = map.next_value()?,
+                    "length" => length = map.next_value()?,
+                    "attributes" => attributes = map.next_value()?,
+                    "children" => {
+                        children = map.next_value_seed(ChildrenSeed {
+                            arena: &mut *self.arena,
+                        })?
+                    }
+                    _ => {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+
+            let node = match node_type.ok_or_else(|| A::Error::missing_field("type"))? {
+                NodeType::Leaf => TreeNode::Leaf {
+                    taxon: taxon.ok_or_else(|| A::Error::missing_field("taxon"))?,
+                    length,
+                    attributes,
+                },
+                NodeType::InternalNode => TreeNode::InternalNode {
+                    label,
+                    length,
+                    attributes,
+                },
+                NodeType::Root => TreeNode::Root { label, attributes },
+            };
+            let node_id = self.arena.new_node(node);
+            for child_id in children {
+                node_id.append(child_id, self.arena);
+            }
+            Ok(node_id)
+        }
+    }
+
+    struct ChildrenSeed<'a> {
+        arena: &'a mut Arena<TreeNode>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for ChildrenSeed<'_> {
+        type Value = Vec<NodeId>;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Vec<NodeId>, D::Error> {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for ChildrenSeed<'_> {
+        type Value = Vec<NodeId>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a list of tree nodes")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<NodeId>, A::Error> {
+            let mut children = vec![];
+            while let Some(child_id) = seq.next_element_seed(NodeSeed {
+                arena: &mut *self.arena,
+            })? {
+                children.push(child_id);
+            }
+            Ok(children)
+        }
+    }
+
+    /// Deserializes the root of a tree, if it has one.
+    struct RootSeed<'a> {
+        arena: &'a mut Arena<TreeNode>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for RootSeed<'_> {
+        type Value = ();
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+            deserializer.deserialize_option(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for RootSeed<'_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a tree node or null")
+        }
+
+        fn visit_none<E: Error>(self) -> Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+            NodeSeed { arena: self.arena }.deserialize(deserializer)?;
+            Ok(())
+        }
+    }
+
+    struct TreeVisitor;
+
+    impl<'de> Visitor<'de> for TreeVisitor {
+        type Value = Tree;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a tree")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Tree, A::Error> {
+            let mut tree = Arena::new();
+            let mut name = None;
+            let mut rooted = None;
+
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "name" => name = Some(map.next_value()?),
+                    "rooted" => rooted = Some(map.next_value()?),
+                    "root" if tree.count() > 0 => return Err(A::Error::duplicate_field("root")),
+                    "root" => map.next_value_seed(RootSeed { arena: &mut tree })?,
+                    _ => {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+
+            Ok(Tree {
+                tree,
+                name: name.ok_or_else(|| A::Error::missing_field("name"))?,
+                rooted: rooted.ok_or_else(|| A::Error::missing_field("rooted"))?,
+            })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Tree {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_map(TreeVisitor)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use indextree::Arena;
+        use serde_json::json;
+
+        use crate::{
+            lexer::{lexer::Lexer, tokens::Tokens},
+            parser::parser::Parser,
+            types::{
+                nexus::{Nexus, NexusBlock},
+                tree::{Tree, TreeNode},
+            },
+        };
+
+        fn parse(text: &str) -> Nexus {
+            let lexer = Lexer::new(text);
+            Parser::new(Tokens::new(&lexer)).parse().unwrap()
+        }
+
+        fn parse_tree(newick: &str) -> Tree {
+            let nexus = parse(&format!("#NEXUS\nBEGIN TREES;\nTREE t = {}\nEND;", newick));
+            match &nexus.blocks[0] {
+                NexusBlock::TreesBlock(_, trees) => trees[0].clone(),
+                _ => unreachable!(),
+            }
+        }
+
+        fn round_trip(tree: &Tree) -> Tree {
+            let json = serde_json::to_string(tree).unwrap();
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            deserializer.disable_recursion_limit();
+            serde::Deserialize::deserialize(&mut deserializer).unwrap()
+        }
+
+        #[test]
+        fn test_tree_json_schema() {
+            let nexus = parse(
+                "#NEXUS
+                BEGIN TREES;
+                    TREE t1 = ((A:1,B:2)95:0.5,C:3);
+                END;",
+            );
+
+            assert_eq!(
+                serde_json::to_value(&nexus).unwrap(),
+                json!({
+                    "blocks": [{"TreesBlock": [{}, [{
+                        "name": "t1",
+                        "rooted": false,
+                        "root": {
+                            "type": "root", "label": null,
+                            "children": [
+                                {"type": "internal_node", "label": "95", "length": 0.5, "children": [
+                                    {"type": "leaf", "taxon": 0, "length": 1.0},
+                                    {"type": "leaf", "taxon": 1, "length": 2.0}
+                                ]},
+                                {"type": "leaf", "taxon": 2, "length": 3.0}
+                            ]
+                        }
+                    }]]}],
+                    "taxa": ["A", "B", "C"]
+                })
+            );
+        }
+
+        #[test]
+        fn test_nexus_json_round_trip() {
+            let nexus = parse(
+                "#NEXUS
+                BEGIN TAXA;
+                    DIMENSIONS NTAX=3;
+                    TAXLABELS Apes Humans 'Pan troglodytes';
+                END;
+                BEGIN CHARACTERS;
+                    DIMENSIONS NCHAR=4;
+                    FORMAT DATATYPE=DNA MISSING=N GAP=.;
+                    MATRIX
+                        Apes AC.T
+                        Humans ACNT
+                        'Pan troglodytes' A{CT}GT
+                    ;
+                END;
+                BEGIN TREES;
+                    TRANSLATE 1 Apes, 2 Humans, 3 'Pan troglodytes';
+                    TREE t1 = ((1:1,2:2.5e-3)label:0.5,3);
+                    TREE t2 = (3,(2,1));
+                END;",
+            );
+
+            let json = serde_json::to_string(&nexus).unwrap();
+            let result: Nexus = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(result, nexus);
+        }
+
+        #[test]
+        fn test_rerooted_tree_json_round_trip() {
+            let mut tree = parse_tree("((A:1,B:2)[&rate=0.5]:0.5,(C:1,D:1):2);");
+            tree.reroot_on_outgroup(&[2]).unwrap();
+
+            assert_eq!(round_trip(&tree), tree);
+        }
+
+        #[test]
+        fn test_pruned_tree_json_round_trip() {
+            let mut tree = parse_tree("((A:1,B:2):0.5,(C:1,(D:1,E:1):1):2);");
+            tree.prune(&[1, 3]);
+
+            assert_eq!(round_trip(&tree), tree);
+        }
+
+        #[test]
+        fn test_deep_tree_json_round_trip() {
+            // a caterpillar tree 100,000 nodes deep, built in postorder like the
+            // parser does
+            let mut tree = Tree {
+                tree: Arena::new(),
+                name: String::from("t"),
+                rooted: true,
+            };
+            let mut subtree = tree.tree.new_node(TreeNode::new_leaf(0));
+            for taxon in 1..100_000 {
+                let leaf = tree
+                    .tree
+                    .new_node(TreeNode::new_leaf(taxon).with_length(1.0));
+                let node = match taxon {
+                    99_999 => TreeNode::new_root(),
+                    _ => TreeNode::new_internal(),
+                };
+                let node_id = tree.tree.new_node(node);
+                node_id.append(subtree, &mut tree.tree);
+                node_id.append(leaf, &mut tree.tree);
+                subtree = node_id;
+            }
+
+            assert_eq!(round_trip(&tree), tree);
+        }
+
+        #[test]
+        fn test_invalid_tree_json() {
+            for root in [
+                // a node without a type
+                json!({"taxon": 0}),
+                // a leaf without a taxon
+                json!({"type": "root", "label": null, "children": [{"type": "leaf"}]}),
+                // children which are not nodes
+                json!({"type": "root", "label": null, "children": [0, 1]}),
+            ] {
+                let tree = json!({"name": "t", "rooted": false, "root": root});
+                assert!(serde_json::from_value::<Tree>(tree).is_err());
+            }
+        }
+    }
+}
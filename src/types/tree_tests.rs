@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tests {
+    use indextree::{Arena, NodeId};
+
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::{
+            clades::Clade,
+            nexus::NexusBlock,
+            tree::{Tree, TreeNode},
+        },
+    };
+
+    fn parse_tree(newick: &str) -> Tree {
+        let lexer = Lexer::new(newick);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse_newick().unwrap();
+        match nexus.blocks.into_iter().next() {
+            Some(NexusBlock::TreesBlock(_, trees)) => trees.into_iter().next().unwrap(),
+            _ => panic!("Expected a trees block."),
+        }
+    }
+
+    /// Builds ((0:1,1:1):2,2:3) with the root inserted first, unlike the parser.
+    fn tree_built_top_down() -> (Tree, [NodeId; 5]) {
+        let mut arena = Arena::new();
+        let root = arena.new_node(TreeNode::new_root());
+        let inner = arena.new_node(TreeNode::new_internal().with_length(2.0));
+        let a = arena.new_node(TreeNode::new_leaf(0).with_length(1.0));
+        let b = arena.new_node(TreeNode::new_leaf(1).with_length(1.0));
+        let c = arena.new_node(TreeNode::new_leaf(2).with_length(3.0));
+        root.append(inner, &mut arena);
+        root.append(c, &mut arena);
+        inner.append(a, &mut arena);
+        inner.append(b, &mut arena);
+
+        let tree = Tree {
+            tree: arena,
+            name: "t".to_string(),
+            rooted: true,
+        };
+        (tree, [root, inner, a, b, c])
+    }
+
+    #[test]
+    fn test_traversals_do_not_depend_on_insertion_order() {
+        let (tree, [root, inner, a, b, c]) = tree_built_top_down();
+
+        assert_eq!(tree.root(), Some(root));
+        assert_eq!(
+            tree.preorder().collect::<Vec<_>>(),
+            vec![root, inner, a, b, c]
+        );
+        assert_eq!(
+            tree.postorder().collect::<Vec<_>>(),
+            vec![a, b, inner, c, root]
+        );
+        assert_eq!(tree.leaves().collect::<Vec<_>>(), vec![a, b, c]);
+        assert_eq!(tree.children(root).collect::<Vec<_>>(), vec![inner, c]);
+        assert_eq!(tree.parent(a), Some(inner));
+        assert_eq!(tree.parent(root), None);
+
+        let parsed = parse_tree("((A:1,B:1):2,C:3);");
+        let taxa = |tree: &Tree| -> Vec<Option<usize>> {
+            tree.preorder()
+                .map(|node_id| tree.node(node_id).taxon())
+                .collect()
+        };
+        assert_eq!(taxa(&parsed), taxa(&tree));
+        assert!(matches!(
+            parsed.node(parsed.root().unwrap()),
+            TreeNode::Root { .. }
+        ));
+    }
+
+    #[test]
+    fn test_mrca() {
+        let (tree, [root, inner, a, _, _]) = tree_built_top_down();
+
+        assert_eq!(tree.mrca(&[0, 1]), Some(inner));
+        assert_eq!(tree.mrca(&[1, 0]), Some(inner));
+        assert_eq!(tree.mrca(&[0, 2]), Some(root));
+        assert_eq!(tree.mrca(&[0, 1, 2]), Some(root));
+        assert_eq!(tree.mrca(&[0]), Some(a));
+        assert_eq!(tree.mrca(&[0, 7]), None);
+        assert_eq!(tree.mrca(&[]), None);
+    }
+
+    #[test]
+    fn test_heights_and_distances() {
+        let (tree, [root, inner, a, _, c]) = tree_built_top_down();
+
+        assert_eq!(tree.distance_to_root(root), 0.0);
+        assert_eq!(tree.distance_to_root(a), 3.0);
+        assert_eq!(tree.node_height(root), 3.0);
+        assert_eq!(tree.node_height(inner), 1.0);
+        assert_eq!(tree.node_height(c), 0.0);
+        assert!(tree.is_ultrametric(1e-9));
+
+        let tree = parse_tree("((A:1,B:1.5):2,C:3);");
+        assert!(!tree.is_ultrametric(0.1));
+        assert!(tree.is_ultrametric(0.5));
+        assert_eq!(tree.node_height(tree.leaf(0).unwrap()), 0.5);
+    }
+
+    fn clade(taxa_count: usize, taxa: &[usize]) -> Clade {
+        let mut clade = Clade::with_capacity(taxa_count);
+        clade.extend(taxa.iter().copied());
+        clade
+    }
+
+    #[test]
+    fn test_clades() {
+        let (tree, [root, inner, a, _, _]) = tree_built_top_down();
+        let node_clades = tree.node_clades(4);
+
+        assert_eq!(node_clades.len(), 5);
+        assert!(node_clades.contains(&(a, clade(4, &[0]))));
+        assert!(node_clades.contains(&(inner, clade(4, &[0, 1]))));
+        assert_eq!(node_clades.last(), Some(&(root, clade(4, &[0, 1, 2]))));
+        assert_eq!(
+            tree.clades(4),
+            vec![clade(4, &[0, 1]), clade(4, &[0, 1, 2])]
+        );
+    }
+
+    #[test]
+    fn test_splits_do_not_depend_on_rooting() {
+        let text = "#NEXUS
+        BEGIN TAXA;
+            DIMENSIONS NTAX=6;
+            TAXLABELS A B C D E F;
+        END;
+        BEGIN TREES;
+            TREE t1 = ((A,B),(C,(D,(E,F))));
+            TREE t2 = (A,(B,(C,(D,(E,F)))));
+            TREE t3 = ((A,B),C,(D,(E,F)));
+            TREE t4 = ((E,F),(D,(C,(A,B))));
+        END;";
+        let lexer = Lexer::new(text);
+        let nexus = Parser::new(Tokens::new(&lexer)).parse().unwrap();
+        let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.get(1) else {
+            panic!("Expected a trees block.");
+        };
+
+        // splits are the side without A
+        let expected = vec![
+            clade(6, &[4, 5]),
+            clade(6, &[3, 4, 5]),
+            clade(6, &[2, 3, 4, 5]),
+        ];
+        for tree in trees {
+            assert_eq!(tree.splits(6), expected, "{}", tree.name);
+        }
+
+        // but the rooted clades differ
+        assert_ne!(trees[0].clades(6), trees[1].clades(6));
+    }
+
+    #[test]
+    fn test_split_lengths() {
+        let tree = parse_tree("((A:1,B:2):0.5,(C:3,D:4):1.5);");
+        let split_lengths = tree.split_lengths(4);
+
+        // the two root branches form a single split
+        assert_eq!(split_lengths.len(), 5);
+        assert_eq!(split_lengths[&clade(4, &[2, 3])], 2.0);
+        assert_eq!(split_lengths[&clade(4, &[1])], 2.0);
+        assert_eq!(split_lengths[&clade(4, &[1, 2, 3])], 1.0);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::types::{
+    characters::{CharacterMatrix, PhylipFormat},
+    taxa::Taxa,
+};
+
+/// The number of states per line of FASTA files and per block of interleaved PHYLIP
+/// files.
+const LINE_WIDTH: usize = 60;
+
+/// Writes a character matrix as PHYLIP. Names are truncated to 10 characters in
+/// strict PHYLIP and have their whitespace replaced by underscores in relaxed PHYLIP.
+pub fn write_phylip(matrix: &CharacterMatrix, taxa: &Taxa, format: PhylipFormat) -> String {
+    let names = matrix
+        .sequences
+        .iter()
+        .map(|(taxon, _)| {
+            let name = taxa.label(*taxon).unwrap_or_default();
+            match format.strict {
+                true => name.chars().take(10).collect(),
+                false => name.replace(char::is_whitespace, "_"),
+            }
+        })
+        .collect::<Vec<String>>();
+    let width = match format.strict {
+        true => 10,
+        false => {
+            names
+                .iter()
+                .map(|name| name.chars().count())
+                .max()
+                .unwrap_or_default()
+                + 1
+        }
+    };
+
+    let mut content = format!("{} {}\n", matrix.sequences.len(), matrix.nchar());
+
+    if !format.interleaved {
+        for (name, (_, sequence)) in names.iter().zip(&matrix.sequences) {
+            content.push_str(&format!("{:width$}{}\n", name, sequence, width = width));
+        }
+        return content;
+    }
+
+    let blocks = matrix
+        .sequences
+        .iter()
+        .map(|(_, sequence)| chunks(sequence))
+        .collect::<Vec<_>>();
+    let block_count = blocks.iter().map(Vec::len).max().unwrap_or_default();
+
+    for block in 0..block_count {
+        if block > 0 {
+            content.push('\n');
+        }
+        for (name, chunks) in names.iter().zip(&blocks) {
+            if block == 0 {
+                content.push_str(&format!("{:width$}", name, width = width));
+            }
+            content.push_str(chunks.get(block).copied().unwrap_or_default());
+            content.push('\n');
+        }
+    }
+
+    content
+}
+
+/// Writes a character matrix as FASTA with lines of 60 states.
+pub fn write_fasta(matrix: &CharacterMatrix, taxa: &Taxa) -> String {
+    let mut content = String::new();
+
+    for (taxon, sequence) in &matrix.sequences {
+        content.push_str(&format!(">{}\n", taxa.label(*taxon).unwrap_or_default()));
+        for chunk in chunks(sequence) {
+            content.push_str(chunk);
+            content.push('\n');
+        }
+    }
+
+    content
+}
+
+fn chunks(sequence: &str) -> Vec<&str> {
+    let mut chunks = vec![];
+    let mut rest = sequence;
+
+    while !rest.is_empty() {
+        let end = rest
+            .char_indices()
+            .nth(LINE_WIDTH)
+            .map_or(rest.len(), |(index, _)| index);
+        chunks.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+
+    chunks
+}
@@ -0,0 +1,32 @@
+pub mod alignment;
+pub mod newick;
+pub mod nexml;
+pub mod nexus;
+pub mod phyloxml;
+mod writer_tests;
+
+/// Quotes a label if it contains whitespace or punctuation. Labels keep the doubled
+/// single quotes of their quoted form when parsed, so they are not escaped again.
+pub(crate) fn quote_label(label: &str) -> String {
+    let needs_quotes = label.is_empty()
+        || label
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "()[]{}/\\,;:=*'\"`<>~".contains(c));
+
+    if needs_quotes {
+        format!("'{}'", label)
+    } else {
+        label.to_string()
+    }
+}
+
+/// Escapes text for XML attributes and elements. Labels keep the doubled single
+/// quotes of their quoted NEXUS form, so these are undone first.
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace("''", "'")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
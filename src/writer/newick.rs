@@ -0,0 +1,102 @@
+use indextree::NodeId;
+
+use crate::{
+    types::{
+        taxa::{Taxa, TaxonId},
+        tree::{AttributeValue, Attributes, Tree, TreeNode},
+    },
+    writer::quote_label,
+};
+
+/// Writes a tree in Newick format, terminated by a semicolon. Rooted trees start
+/// with `[&R]`.
+pub fn write_newick(tree: &Tree, taxa: &Taxa) -> String {
+    let mut newick = match tree.rooted {
+        true => String::from("[&R] "),
+        false => String::new(),
+    };
+    newick.push_str(&write_newick_subtree(tree, &|taxon| {
+        quote_label(taxa.label(taxon).unwrap_or_default())
+    }));
+    newick.push(';');
+    newick
+}
+
+/// Writes a tree in Newick format without the terminating semicolon, using the
+/// given function to write the label of a leaf.
+pub(crate) fn write_newick_subtree(tree: &Tree, leaf_label: &dyn Fn(TaxonId) -> String) -> String {
+    let mut newick = String::new();
+
+    if let Some(root) = tree.root() {
+        write_node(tree, root, leaf_label, &mut newick);
+    }
+
+    newick
+}
+
+fn write_node(
+    tree: &Tree,
+    node_id: NodeId,
+    leaf_label: &dyn Fn(TaxonId) -> String,
+    newick: &mut String,
+) {
+    let mut children = node_id.children(&tree.tree).peekable();
+    if children.peek().is_some() {
+        newick.push('(');
+        for (i, child) in children.enumerate() {
+            if i > 0 {
+                newick.push(',');
+            }
+            write_node(tree, child, leaf_label, newick);
+        }
+        newick.push(')');
+    }
+
+    let (label, length) = match tree.tree[node_id].get() {
+        TreeNode::Leaf { taxon, length, .. } => (Some(leaf_label(*taxon)), length),
+        TreeNode::InternalNode { label, length, .. } => (label.as_deref().map(quote_label), length),
+        TreeNode::Root { label, .. } => (label.as_deref().map(quote_label), &None),
+    };
+
+    if let Some(label) = label {
+        newick.push_str(&label);
+    }
+    newick.push_str(&write_attributes(tree.tree[node_id].get().attributes()));
+    if let Some(length) = length {
+        newick.push(':');
+        newick.push_str(&length.to_string());
+    }
+}
+
+/// Writes attributes as a FigTree/BEAST annotation such as `[&rate=1.2,set={A,B}]`,
+/// or nothing if there are none.
+pub(crate) fn write_attributes(attributes: &Attributes) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+
+    let attributes: Vec<String> = attributes
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, write_attribute_value(value)))
+        .collect();
+    format!("[&{}]", attributes.join(","))
+}
+
+fn write_attribute_value(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::Number(number) => number.to_string(),
+        AttributeValue::Text(text)
+            if text.is_empty()
+                || text
+                    .chars()
+                    .any(|c| c.is_whitespace() || "[]{},=\"'".contains(c)) =>
+        {
+            format!("\"{}\"", text)
+        }
+        AttributeValue::Text(text) => text.clone(),
+        AttributeValue::List(values) => {
+            let values: Vec<String> = values.iter().map(write_attribute_value).collect();
+            format!("{{{}}}", values.join(","))
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use indextree::NodeId;
+
+use crate::{
+    types::{
+        characters::{CharacterMatrix, DataType},
+        nexus::{Nexus, NexusBlock},
+        taxa::TaxonId,
+        tree::{Tree, TreeNode},
+    },
+    writer::escape_xml,
+};
+
+/// The id of the OTU of a taxon. NeXML and PhyloXML output use the same ids, which
+/// are based on the position of the taxon in `Nexus::taxa`.
+pub(crate) fn otu_id(taxon: TaxonId) -> String {
+    format!("otu{}", taxon)
+}
+
+/// Writes a parsed NEXUS file as NeXML. All taxa are written to a single OTUs
+/// block, which the character matrices and trees refer to.
+pub fn write_nexml(nexus: &Nexus) -> String {
+    let mut content = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<nex:nexml xmlns:nex=\"http://www.nexml.org/2009\" xmlns=\"http://www.nexml.org/2009\" ",
+        "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" version=\"0.9\" generator=\"nexus\">\n",
+    ));
+
+    content.push_str("  <otus id=\"otus\">\n");
+    for (taxon, label) in nexus.taxa.labels().iter().enumerate() {
+        content.push_str(&format!(
+            "    <otu id=\"{}\" label=\"{}\"/>\n",
+            otu_id(taxon),
+            escape_xml(label)
+        ));
+    }
+    content.push_str("  </otus>\n");
+
+    for (i, block) in nexus.blocks.iter().enumerate() {
+        match block {
+            NexusBlock::CharactersBlock(matrix) => {
+                write_characters(matrix, &format!("characters{}", i), &mut content)
+            }
+            NexusBlock::TreesBlock(_, trees) => {
+                content.push_str(&format!("  <trees id=\"trees{}\" otus=\"otus\">\n", i));
+                for (j, tree) in trees.iter().enumerate() {
+                    write_tree(tree, &format!("tree{}_{}", i, j), &mut content);
+                }
+                content.push_str("  </trees>\n");
+            }
+            NexusBlock::TaxaBlock(..) => {}
+        }
+    }
+
+    content.push_str("</nex:nexml>\n");
+    content
+}
+
+fn write_characters(matrix: &CharacterMatrix, id: &str, content: &mut String) {
+    let (class, unknown) = match matrix.datatype {
+        DataType::Dna | DataType::Nucleotide => ("DnaSeqs", 'N'),
+        DataType::Rna => ("RnaSeqs", 'N'),
+        DataType::Protein => ("ProteinSeqs", 'X'),
+        DataType::Standard => ("StandardSeqs", '?'),
+    };
+    let is_standard = matrix.datatype == DataType::Standard;
+
+    content.push_str(&format!(
+        "  <characters id=\"{}\" otus=\"otus\" xsi:type=\"nex:{}\">\n",
+        id, class
+    ));
+
+    // standard states have to be declared, molecular ones are predefined
+    let symbols = match is_standard {
+        true => standard_symbols(matrix),
+        false => vec![],
+    };
+    if is_standard {
+        content.push_str("    <format>\n");
+        content.push_str(&format!("      <states id=\"{}_states\">\n", id));
+        for (i, symbol) in symbols.iter().enumerate() {
+            content.push_str(&format!(
+                "        <state id=\"{}_s{}\" label=\"{}\" symbol=\"{}\"/>\n",
+                id,
+                i,
+                escape_xml(&symbol.to_string()),
+                i
+            ));
+        }
+        content.push_str(&format!(
+            "        <uncertain_state_set id=\"{}_gap\" symbol=\"{}\"/>\n",
+            id,
+            symbols.len()
+        ));
+        content.push_str(&format!(
+            "        <uncertain_state_set id=\"{}_missing\" symbol=\"{}\">\n",
+            id,
+            symbols.len() + 1
+        ));
+        for i in 0..symbols.len() {
+            content.push_str(&format!("          <member state=\"{}_s{}\"/>\n", id, i));
+        }
+        content.push_str("        </uncertain_state_set>\n");
+        content.push_str("      </states>\n");
+        for i in 0..matrix.nchar() {
+            content.push_str(&format!(
+                "      <char id=\"{}_c{}\" states=\"{}_states\"/>\n",
+                id, i, id
+            ));
+        }
+        content.push_str("    </format>\n");
+    }
+
+    content.push_str("    <matrix>\n");
+    for (taxon, sequence) in &matrix.sequences {
+        let states = states(sequence, matrix.datatype)
+            .map(|state| match state {
+                State::Unknown if is_standard => (symbols.len() + 1).to_string(),
+                State::Unknown => '?'.to_string(),
+                State::Single(state) if state == matrix.missing => match is_standard {
+                    true => (symbols.len() + 1).to_string(),
+                    false => '?'.to_string(),
+                },
+                State::Single(state) if state == matrix.gap => match is_standard {
+                    true => symbols.len().to_string(),
+                    false => '-'.to_string(),
+                },
+                State::Single(state) if is_standard => symbols
+                    .iter()
+                    .position(|symbol| *symbol == state)
+                    .unwrap_or(symbols.len() + 1)
+                    .to_string(),
+                State::Single(state) => state.to_string(),
+                State::Ambiguous(code) => code.unwrap_or(unknown).to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let separator = if is_standard { " " } else { "" };
+        content.push_str(&format!(
+            "      <row id=\"{}_row{}\" otu=\"{}\">\n        <seq>{}</seq>\n      </row>\n",
+            id,
+            taxon,
+            otu_id(*taxon),
+            escape_xml(&states.join(separator))
+        ));
+    }
+    content.push_str("    </matrix>\n");
+    content.push_str("  </characters>\n");
+}
+
+enum State {
+    Single(char),
+    /// A polymorphism or uncertainty, with its IUPAC code if there is one.
+    Ambiguous(Option<char>),
+    /// A polymorphism of standard data, which is written as missing.
+    Unknown,
+}
+
+fn states(sequence: &str, datatype: DataType) -> impl Iterator<Item = State> + '_ {
+    let mut chars = sequence.chars();
+
+    std::iter::from_fn(move || {
+        let state = chars.next()?;
+        if state != '{' && state != '(' {
+            return Some(State::Single(state));
+        }
+
+        let mut group: Vec<char> = chars
+            .by_ref()
+            .take_while(|state| *state != '}' && *state != ')')
+            .map(|state| state.to_ascii_uppercase())
+            .collect();
+        group.sort();
+        group.dedup();
+
+        Some(match datatype {
+            DataType::Standard => State::Unknown,
+            DataType::Protein => State::Ambiguous(None),
+            _ => State::Ambiguous(iupac_code(&group)),
+        })
+    })
+}
+
+fn iupac_code(group: &[char]) -> Option<char> {
+    let group: String = group
+        .iter()
+        .map(|state| if *state == 'U' { 'T' } else { *state })
+        .collect();
+
+    match group.as_str() {
+        "AG" => Some('R'),
+        "CT" => Some('Y'),
+        "GT" => Some('K'),
+        "AC" => Some('M'),
+        "CG" => Some('S'),
+        "AT" => Some('W'),
+        "CGT" => Some('B'),
+        "AGT" => Some('D'),
+        "ACT" => Some('H'),
+        "ACG" => Some('V'),
+        "ACGT" => Some('N'),
+        _ => None,
+    }
+}
+
+/// The distinct states of a standard matrix in the order of their first occurrence.
+fn standard_symbols(matrix: &CharacterMatrix) -> Vec<char> {
+    let mut symbols = vec![];
+
+    for (_, sequence) in &matrix.sequences {
+        for state in states(sequence, DataType::Standard) {
+            if let State::Single(state) = state
+                && state != matrix.missing
+                && state != matrix.gap
+                && !symbols.contains(&state)
+            {
+                symbols.push(state);
+            }
+        }
+    }
+
+    symbols
+}
+
+fn write_tree(tree: &Tree, id: &str, content: &mut String) {
+    content.push_str(&format!(
+        "    <tree id=\"{}\" label=\"{}\" xsi:type=\"nex:FloatTree\">\n",
+        id,
+        escape_xml(&tree.name)
+    ));
+
+    let mut edges = String::new();
+    if let Some(root) = tree.root() {
+        write_node(tree, root, id, content, &mut edges);
+    }
+    content.push_str(&edges);
+
+    content.push_str("    </tree>\n");
+}
+
+fn write_node(tree: &Tree, node_id: NodeId, tree_id: &str, nodes: &mut String, edges: &mut String) {
+    let id = format!("{}_n{}", tree_id, usize::from(node_id));
+
+    let mut attributes = String::new();
+    match tree.tree[node_id].get() {
+        TreeNode::Leaf { taxon, .. } => {
+            attributes.push_str(&format!(" otu=\"{}\"", otu_id(*taxon)))
+        }
+        TreeNode::InternalNode { label, .. } | TreeNode::Root { label, .. } => {
+            if let Some(label) = label {
+                attributes.push_str(&format!(" label=\"{}\"", escape_xml(label)));
+            }
+        }
+    }
+    if matches!(tree.tree[node_id].get(), TreeNode::Root { .. }) && tree.rooted {
+        attributes.push_str(" root=\"true\"");
+    }
+    nodes.push_str(&format!("      <node id=\"{}\"{}/>\n", id, attributes));
+
+    for child_id in node_id.children(&tree.tree) {
+        let length = tree.node(child_id).length();
+        edges.push_str(&format!(
+            "      <edge id=\"{}_e{}\" source=\"{}\" target=\"{}_n{}\"{}/>\n",
+            tree_id,
+            usize::from(child_id),
+            id,
+            tree_id,
+            usize::from(child_id),
+            length
+                .map(|length| format!(" length=\"{}\"", length))
+                .unwrap_or_default()
+        ));
+
+        write_node(tree, child_id, tree_id, nodes, edges);
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::{
+    types::{
+        nexus::{Nexus, NexusBlock},
+        taxa::TaxonId,
+    },
+    writer::{newick::write_newick_subtree, quote_label},
+};
+
+/// Writes a parsed NEXUS file. Leaves of trees are written using the keys of the
+/// translate table of their trees block where there is one.
+pub fn write_nexus(nexus: &Nexus) -> String {
+    let mut content = String::from("#NEXUS\n");
+    let has_taxa_block = nexus
+        .blocks
+        .iter()
+        .any(|block| matches!(block, NexusBlock::TaxaBlock(..)));
+
+    for block in &nexus.blocks {
+        content.push('\n');
+
+        match block {
+            NexusBlock::TaxaBlock(dimensions, labels) => {
+                content.push_str("BEGIN TAXA;\n");
+                content.push_str(&format!("\tDIMENSIONS NTAX={};\n", dimensions));
+                content.push_str("\tTAXLABELS");
+                for label in labels {
+                    content.push_str(&format!("\n\t\t{}", quote_label(label)));
+                }
+                content.push_str("\n\t;\nEND;\n");
+            }
+            NexusBlock::TreesBlock(translations, trees) => {
+                content.push_str("BEGIN TREES;\n");
+
+                let mut translations = translations
+                    .iter()
+                    .map(|(key, taxon)| {
+                        let label = nexus.taxa.label(*taxon).unwrap_or_default();
+                        (*taxon, key.as_str(), label)
+                    })
+                    .collect::<Vec<_>>();
+                translations.sort();
+
+                if !translations.is_empty() {
+                    content.push_str("\tTRANSLATE");
+                    for (i, (_, key, label)) in translations.iter().enumerate() {
+                        let separator = if i + 1 < translations.len() { "," } else { "" };
+                        content.push_str(&format!(
+                            "\n\t\t{} {}{}",
+                            key,
+                            quote_label(label),
+                            separator
+                        ));
+                    }
+                    content.push_str("\n\t;\n");
+                }
+
+                let keys: HashMap<TaxonId, &str> = translations
+                    .iter()
+                    .map(|(taxon, key, _)| (*taxon, *key))
+                    .collect();
+                let leaf_label = |taxon: TaxonId| match keys.get(&taxon) {
+                    Some(key) => quote_label(key),
+                    None => quote_label(nexus.taxa.label(taxon).unwrap_or_default()),
+                };
+
+                for tree in trees {
+                    content.push_str(&format!(
+                        "\tTREE {} = {}{};\n",
+                        quote_label(&tree.name),
+                        if tree.rooted { "[&R] " } else { "" },
+                        write_newick_subtree(tree, &leaf_label)
+                    ));
+                }
+
+                content.push_str("END;\n");
+            }
+            NexusBlock::CharactersBlock(matrix) => {
+                // without a TAXA block the matrix has to define its taxa itself
+                if has_taxa_block {
+                    content.push_str("BEGIN CHARACTERS;\n");
+                    content.push_str(&format!("\tDIMENSIONS NCHAR={};\n", matrix.nchar()));
+                } else {
+                    content.push_str("BEGIN DATA;\n");
+                    content.push_str(&format!(
+                        "\tDIMENSIONS NTAX={} NCHAR={};\n",
+                        matrix.sequences.len(),
+                        matrix.nchar()
+                    ));
+                }
+                content.push_str(&format!(
+                    "\tFORMAT DATATYPE={} MISSING={} GAP={};\n",
+                    matrix.datatype, matrix.missing, matrix.gap
+                ));
+
+                let labels = matrix
+                    .sequences
+                    .iter()
+                    .map(|(taxon, _)| quote_label(nexus.taxa.label(*taxon).unwrap_or_default()))
+                    .collect::<Vec<_>>();
+                let width = labels.iter().map(|label| label.chars().count()).max();
+
+                content.push_str("\tMATRIX");
+                for (label, (_, sequence)) in labels.iter().zip(&matrix.sequences) {
+                    content.push_str(&format!(
+                        "\n\t\t{:width$}  {}",
+                        label,
+                        sequence,
+                        width = width.unwrap_or_default()
+                    ));
+                }
+                content.push_str("\n\t;\nEND;\n");
+            }
+        }
+    }
+
+    content
+}
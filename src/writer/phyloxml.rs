@@ -0,0 +1,68 @@
+use indextree::NodeId;
+
+use crate::{
+    types::{
+        taxa::Taxa,
+        tree::{Tree, TreeNode},
+    },
+    writer::{escape_xml, nexml::otu_id},
+};
+
+/// Writes a single tree as PhyloXML. Leaves carry the name of their taxon and the
+/// same taxonomy id as the OTU of the taxon in NeXML output.
+pub fn write_phyloxml(tree: &Tree, taxa: &Taxa) -> String {
+    let mut content = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<phyloxml xmlns=\"http://www.phyloxml.org\" ",
+        "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+        "xsi:schemaLocation=\"http://www.phyloxml.org http://www.phyloxml.org/1.10/phyloxml.xsd\">\n",
+    ));
+
+    content.push_str(&format!("  <phylogeny rooted=\"{}\">\n", tree.rooted));
+    content.push_str(&format!("    <name>{}</name>\n", escape_xml(&tree.name)));
+
+    if let Some(root) = tree.root() {
+        write_clade(tree, root, taxa, 2, &mut content);
+    }
+
+    content.push_str("  </phylogeny>\n");
+    content.push_str("</phyloxml>\n");
+    content
+}
+
+fn write_clade(tree: &Tree, node_id: NodeId, taxa: &Taxa, depth: usize, content: &mut String) {
+    let indent = "  ".repeat(depth);
+    content.push_str(&format!("{}<clade>\n", indent));
+
+    let (name, length, taxon) = match tree.tree[node_id].get() {
+        TreeNode::Leaf { taxon, length, .. } => (taxa.label(*taxon), *length, Some(*taxon)),
+        TreeNode::InternalNode { label, length, .. } => (label.as_deref(), *length, None),
+        TreeNode::Root { label, .. } => (label.as_deref(), None, None),
+    };
+
+    // the order of the elements is fixed by the schema
+    if let Some(name) = name {
+        content.push_str(&format!("{}  <name>{}</name>\n", indent, escape_xml(name)));
+    }
+    if let Some(length) = length {
+        content.push_str(&format!(
+            "{}  <branch_length>{}</branch_length>\n",
+            indent, length
+        ));
+    }
+    if let Some(taxon) = taxon {
+        content.push_str(&format!(
+            "{}  <taxonomy>\n{}    <id>{}</id>\n{}  </taxonomy>\n",
+            indent,
+            indent,
+            otu_id(taxon),
+            indent
+        ));
+    }
+
+    for child_id in node_id.children(&tree.tree) {
+        write_clade(tree, child_id, taxa, depth + 1, content);
+    }
+
+    content.push_str(&format!("{}</clade>\n", indent));
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::{lexer::Lexer, tokens::Tokens},
+        parser::parser::Parser,
+        types::nexus::{Nexus, NexusBlock},
+        writer::{
+            newick::write_newick, nexml::write_nexml, nexus::write_nexus, phyloxml::write_phyloxml,
+        },
+    };
+
+    const TREES: &str = "#NEXUS
+        BEGIN taxa;
+            DIMENSIONS ntax=4;
+            TAXLABELS Apes Humans 'Gorilla 1' 'Chimpanz''ee';
+        END;
+
+        BEGIN trees;
+            Translate
+                1 Apes,
+                2 Humans,
+                some key 'Gorilla 1',
+                4 'Chimpanz''ee';
+            TREE t1 = ((1:1, 2:2.5e-3):0.5, 'some key':3);
+            TREE 'tree 2' = ((4, 'some key'), (2, 1));
+        END;
+        ";
+
+    fn parse(text: &str) -> Nexus {
+        let lexer = Lexer::new(text);
+        let tokens = Tokens::new(&lexer);
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    #[test]
+    fn test_write_newick() {
+        let nexus = parse(TREES);
+        let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.get(1) else {
+            panic!("Expected a trees block.");
+        };
+
+        assert_eq!(
+            write_newick(&trees[0], &nexus.taxa),
+            "((Apes:1,Humans:0.0025):0.5,'Gorilla 1':3);"
+        );
+        assert_eq!(
+            write_newick(&trees[1], &nexus.taxa),
+            "(('Chimpanz''ee','Gorilla 1'),(Humans,Apes));"
+        );
+    }
+
+    #[test]
+    fn test_write_nexus_round_trip() {
+        let nexus = parse(TREES);
+        let written = write_nexus(&nexus);
+
+        assert!(written.contains("TREE t1 = ((1:1,2:0.0025):0.5,'some key':3);"));
+        assert_eq!(parse(&written), nexus);
+    }
+
+    /// Reads a document to the end, failing on malformed XML.
+    fn assert_well_formed(xml: &str) {
+        let mut reader = quick_xml::Reader::from_str(xml);
+        reader.config_mut().check_end_names = true;
+        loop {
+            match reader.read_event() {
+                Ok(quick_xml::events::Event::Eof) => break,
+                Ok(_) => {}
+                Err(error) => panic!("Malformed XML: {}\n{}", error, xml),
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_nexml() {
+        let text = TREES.replace(
+            "BEGIN trees;",
+            "BEGIN characters;
+                DIMENSIONS nchar=4;
+                FORMAT datatype=dna missing=? gap=-;
+                MATRIX
+                    Apes AC{AG}T
+                    Humans AC-T
+                    'Gorilla 1' ?CGT
+                    'Chimpanz''ee' ACGT
+                ;
+            END;
+            BEGIN trees;",
+        );
+        let nexus = parse(&text);
+        let nexml = write_nexml(&nexus);
+
+        assert_well_formed(&nexml);
+        assert!(nexml.contains("<otu id=\"otu3\" label=\"Chimpanz&apos;ee\"/>"));
+        assert!(nexml.contains("xsi:type=\"nex:DnaSeqs\""));
+        assert!(
+            nexml.contains("<row id=\"characters1_row0\" otu=\"otu0\">\n        <seq>ACRT</seq>")
+        );
+        assert!(nexml.contains("<node id=\"tree2_0_n1\" otu=\"otu0\"/>"));
+        assert!(nexml.contains("target=\"tree2_0_n1\" length=\"1\"/>"));
+        assert_eq!(nexml.matches("<tree ").count(), 2);
+    }
+
+    #[test]
+    fn test_write_nexml_standard_matrix() {
+        let nexus = parse(
+            "#NEXUS
+            BEGIN DATA;
+                DIMENSIONS ntax=2 nchar=3;
+                MATRIX
+                    a 01?
+                    b 1-{01}
+                ;
+            END;",
+        );
+        let nexml = write_nexml(&nexus);
+
+        assert_well_formed(&nexml);
+        assert!(nexml.contains("xsi:type=\"nex:StandardSeqs\""));
+        assert!(nexml.contains("<seq>0 1 3</seq>"));
+        assert!(nexml.contains("<seq>1 2 3</seq>"));
+    }
+
+    #[test]
+    fn test_write_phyloxml() {
+        let nexus = parse(TREES);
+        let Some(NexusBlock::TreesBlock(_, trees)) = nexus.blocks.get(1) else {
+            panic!("Expected a trees block.");
+        };
+        let phyloxml = write_phyloxml(&trees[0], &nexus.taxa);
+
+        assert_well_formed(&phyloxml);
+        assert!(phyloxml.contains("<name>t1</name>"));
+        assert!(phyloxml.contains(
+            "<name>Gorilla 1</name>\n        <branch_length>3</branch_length>\n        <taxonomy>\n          <id>otu2</id>"
+        ));
+        assert_eq!(phyloxml.matches("<clade>").count(), 5);
+    }
+}